@@ -12,11 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ::allocator_api2::alloc;
 use ::allocator_api2::alloc::Allocator;
 use ::allocator_api2::boxed::Box;
 use ::std::any::Any;
-use ::std::ptr::NonNull;
 
 use crate::cache_mode::CacheMode;
 use crate::cons::Cons;
@@ -40,18 +38,8 @@ where
     /// assert_eq!(list.iter().nth(1).unwrap().downcast_ref::<&str>(), Some(&"hello"));
     /// ```
     pub fn push_any<T: Any>(&self, val: T) -> &T {
-        let sized_box = Box::new_in(Cons::<T, dyn Any, A>::new(val), A::clone(&self.alloc));
-        // Because we are using the non-standard `Box`, we need to manually do the unsized coercions...
-        // Watching the PR:
-        // https://github.com/zakarumych/allocator-api2/pull/23
-        let unsized_box = unsafe {
-            let (sized_ptr, alloc) = Box::into_raw_with_allocator(sized_box);
-            // Pointer unsized coercion!
-            let unsized_ptr: *mut Cons<dyn Any, dyn Any, A> = sized_ptr;
-            Box::from_raw_in(unsized_ptr, alloc)
-        };
         self.push_inner(
-            unsized_box,
+            Self::new_any_cons(val, A::clone(&self.alloc)),
             // Safe because we know the given value is type `T`.
             |c| match c.downcast_ref::<T>() {
                 Some(v) => v,
@@ -59,6 +47,64 @@ where
             },
         )
     }
+
+    /// Stores the value as the only value of type `T` in the list, and returns the old value.
+    ///
+    /// If the list contains a value of type `T`, the first one is replaced in place, so its
+    /// position in the list is kept. Otherwise, the value is pushed to the back of the list.
+    /// Both cases need only a single scan of the list.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    /// use std::any::Any;
+    ///
+    /// let mut list = OnceList::<dyn Any>::new();
+    /// assert_eq!(list.set_by_type(1), None);
+    /// list.push_any("hello");
+    /// assert_eq!(list.set_by_type(2), Some(1));
+    ///
+    /// assert_eq!(list.iter().nth(0).unwrap().downcast_ref::<i32>(), Some(&2));
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    pub fn set_by_type<T: Any>(&mut self, val: T) -> Option<T> {
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        while let Some(next_ref) = next_cell.get() {
+            let is_match = next_ref.val.is::<T>();
+            // Safe because we are sure the `next_cell` value is set.
+            let Some(next_box) = next_cell.get_mut() else {
+                unreachable!("set_by_type: next_cell had value but get_mut() returned None");
+            };
+            if is_match {
+                return match next_box.val.downcast_mut::<T>() {
+                    Some(old) => Some(::std::mem::replace(old, val)),
+                    None => unreachable!("set_by_type: is::<T>() matched but downcast failed"),
+                };
+            }
+            next_cell = &mut next_box.next;
+        }
+
+        // Not found. Push the value to the end of the list, where the scan has stopped.
+        let _ = next_cell.set(Self::new_any_cons(val, A::clone(&self.alloc)));
+        if let Some(inserted) = next_cell.get() {
+            self.cache_mode.on_push_success(&inserted.next);
+        }
+        None
+    }
+
+    fn new_any_cons<T: Any>(val: T, alloc: A) -> Box<Cons<dyn Any, dyn Any, A>, A> {
+        let sized_box = Box::new_in(Cons::<T, dyn Any, A>::new(val), alloc);
+        // Because we are using the non-standard `Box`, we need to manually do the unsized coercions...
+        // Watching the PR:
+        // https://github.com/zakarumych/allocator-api2/pull/23
+        unsafe {
+            let (sized_ptr, alloc) = Box::into_raw_with_allocator(sized_box);
+            // Pointer unsized coercion!
+            let unsized_ptr: *mut Cons<dyn Any, dyn Any, A> = sized_ptr;
+            Box::from_raw_in(unsized_ptr, alloc)
+        }
+    }
 }
 
 impl<A: Allocator, M> OnceListCore<dyn Any, A, M>
@@ -101,29 +147,8 @@ where
     pub fn remove_by_type<T: Any>(&mut self) -> Option<T> {
         self.remove_inner(
             |v| v.is::<T>(),
-            |boxed_cons| {
-                let cons_layout = alloc::Layout::for_value::<Cons<_, _, _>>(&boxed_cons);
-                let (cons_ptr, alloc) = Box::into_raw_with_allocator(boxed_cons);
-
-                let Cons {
-                    next: next_ref,
-                    val: val_any_ref,
-                } = unsafe { &*cons_ptr };
-                // Drop the `next` field.
-                unsafe { ::std::ptr::read(next_ref) };
-
-                let val_ref = match <dyn Any>::downcast_ref::<T>(val_any_ref) {
-                    Some(v) => v,
-                    None => unreachable!("remove_by_type predicate matched but downcast failed"),
-                };
-                let val = unsafe { ::std::ptr::read(val_ref) };
-
-                unsafe {
-                    alloc.deallocate(NonNull::new_unchecked(cons_ptr as *mut u8), cons_layout);
-                }
-
-                val
-            },
+            // Safe because the predicate has checked the value's concrete type is `T`.
+            |boxed_cons| unsafe { Cons::box_into_inner_as::<T>(boxed_cons) },
         )
     }
 }
@@ -0,0 +1,93 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [`bumpalo`] arena integration.
+//!
+//! With the `bumpalo` feature enabled, a list can allocate its nodes in a [`Bump`] arena, e.g.
+//! `OnceList<T, &Bump>`.
+//!
+//! ## Which cache mode to use
+//!
+//! Every cache mode works with an arena. Note that a bump allocator can not reuse the memory of
+//! the removed nodes, so the lists which mostly grow (which is the main use case of this crate)
+//! fit the arenas best. [`WithTail`](crate::WithTail) or [`WithTailLen`](crate::WithTailLen) is
+//! recommended for the large lists, because the pushes are O(1) without walking the nodes
+//! scattered among the other arena allocations.
+//!
+//! ## Drop semantics
+//!
+//! Dropping a list drops every value in it as usual, even though the arena itself never runs
+//! destructors. Each node's memory is "freed" to the arena too, which is mostly a no-op for
+//! [`Bump`]. [`OnceListCore::forget_nodes`] skips the latter entirely, and leaves the memory to
+//! be reclaimed when the arena is reset or dropped.
+
+use ::allocator_api2::boxed::Box;
+use ::bumpalo::Bump;
+
+use crate::cache_mode::{CacheMode, NextSlot};
+use crate::once_list::OnceListCore;
+
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "bumpalo")))]
+impl<'b, T: ?Sized, C> OnceListCore<T, &'b Bump, C>
+where
+    C: CacheMode<T, &'b Bump> + Default,
+{
+    /// Creates a new empty list which allocates its nodes in the given arena.
+    /// This method does not allocate.
+    ///
+    /// ```rust
+    /// use bumpalo::Bump;
+    /// use once_list2::OnceListWithTail;
+    ///
+    /// let bump = Bump::new();
+    /// let list = OnceListWithTail::new_in_bump(&bump);
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.iter().sum::<i32>(), 6);
+    /// ```
+    pub fn new_in_bump(bump: &'b Bump) -> Self {
+        Self {
+            head_slot: NextSlot::new(),
+            alloc: bump,
+            cache_mode: C::default(),
+        }
+    }
+
+    /// Drops all the values in the list, without returning the nodes' memory to the arena.
+    ///
+    /// The memory is reclaimed all at once when the arena is reset or dropped. This saves a
+    /// deallocation call per node for a large list.
+    ///
+    /// ```rust
+    /// use bumpalo::Bump;
+    /// use once_list2::OnceList;
+    ///
+    /// let mut bump = Bump::new();
+    /// let list = OnceList::new_in_bump(&bump);
+    /// list.extend(["a".to_string(), "b".to_string()]);
+    /// list.forget_nodes();
+    /// bump.reset();
+    /// ```
+    pub fn forget_nodes(mut self) {
+        let mut next_box = self.head_slot.take();
+        while let Some(cur_box) = next_box {
+            let (cur_ptr, _) = Box::into_raw_with_allocator(cur_box);
+            // Safe because the node is valid and exclusively owned here. Once the `next` slot is
+            // taken, dropping the node's fields frees nothing but the value's own resources.
+            unsafe {
+                next_box = (*cur_ptr).next.take();
+                ::std::ptr::drop_in_place(cur_ptr);
+            }
+        }
+    }
+}
@@ -15,6 +15,7 @@
 use ::allocator_api2::alloc::Allocator;
 use ::allocator_api2::boxed::Box;
 use ::std::cell::Cell;
+use ::std::cmp::Ordering;
 use ::std::ptr::NonNull;
 
 use crate::cons::Cons;
@@ -35,7 +36,6 @@ mod sealed {
 /// Caching focuses on the tail insertion hot path, but the slot itself is conceptually "the next slot"
 /// in a singly-linked list.
 #[doc(hidden)]
-#[derive(Clone)]
 pub struct NextSlot<T: ?Sized, A: Allocator> {
     cell: OnceCell<Box<Cons<T, T, A>, A>>,
 }
@@ -63,6 +63,130 @@ impl<T: ?Sized, A: Allocator> NextSlot<T, A> {
         self.cell.take()
     }
 
+    /// Unlinks the first node in the chain starting from this slot whose value matches the
+    /// predicate, and reconnects the chain. The returned node's `next` slot is empty.
+    pub(crate) fn unlink_first<P>(&mut self, pred: P) -> Option<Box<Cons<T, T, A>, A>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.unlink_where(pred).next()
+    }
+
+    /// Returns an iterator which unlinks the nodes in the chain starting from this slot whose
+    /// values match the predicate, in order, and reconnects the chain. The returned nodes' `next`
+    /// slots are empty.
+    ///
+    /// The nodes after the last returned one are not visited until the iterator is advanced.
+    pub(crate) fn unlink_where<'s, P>(
+        &'s mut self,
+        mut pred: P,
+    ) -> impl Iterator<Item = Box<Cons<T, T, A>, A>> + 's
+    where
+        P: FnMut(&T) -> bool + 's,
+    {
+        let mut cursor = Some(self);
+        ::std::iter::from_fn(move || loop {
+            let next_cell = cursor.take()?;
+            let next_ref = next_cell.get()?;
+            if pred(&next_ref.val) {
+                // Safe because we are sure the `next_cell` value is set.
+                let Some(mut next_box) = next_cell.take() else {
+                    unreachable!("unlink_where: next_cell had value but take() returned None");
+                };
+
+                // reconnect the list
+                if let Some(next_next) = next_box.next.take() {
+                    let _ = next_cell.set(next_next);
+                }
+                cursor = Some(next_cell);
+                return Some(next_box);
+            }
+            // Safe because we are sure the `next_cell` value is set.
+            let Some(next_box) = next_cell.get_mut() else {
+                unreachable!("unlink_where: next_cell had value but get_mut() returned None");
+            };
+            cursor = Some(&mut next_box.next);
+        })
+    }
+
+    /// Appends the other chain after the last node of this chain.
+    ///
+    /// This method walks this chain to its end, so it is O(n) unless this chain is empty.
+    pub(crate) fn append(&mut self, mut other: Self) {
+        let Some(rest) = other.take() else {
+            return;
+        };
+        let mut next_cell = self;
+        while next_cell.get().is_some() {
+            // Safe because we are sure the `next_cell` value is set.
+            let Some(next_box) = next_cell.get_mut() else {
+                unreachable!("append: next_cell had value but get_mut() returned None");
+            };
+            next_cell = &mut next_box.next;
+        }
+        let _ = next_cell.set(rest);
+    }
+
+    /// Merges the other sorted chain into this sorted chain, by relinking the nodes. The other
+    /// chain is left empty.
+    ///
+    /// The merge is stable: if the values are equal, the nodes in this chain come first.
+    ///
+    /// If `compare` panics, no node is lost: this chain keeps all the nodes of both chains, in
+    /// an unspecified order.
+    pub(crate) fn merge_by<F>(&mut self, other: &mut Self, compare: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        /// Links the merged nodes and the not yet merged nodes back into `lhs` when dropped.
+        struct Guard<'s, T: ?Sized, A: Allocator> {
+            merged: NextSlot<T, A>,
+            lhs: &'s mut NextSlot<T, A>,
+            rhs: &'s mut NextSlot<T, A>,
+        }
+
+        impl<T: ?Sized, A: Allocator> Drop for Guard<'_, T, A> {
+            fn drop(&mut self) {
+                let lhs = ::std::mem::replace(self.lhs, NextSlot::new());
+                let rhs = ::std::mem::replace(self.rhs, NextSlot::new());
+                self.merged.append(lhs);
+                self.merged.append(rhs);
+                *self.lhs = ::std::mem::replace(&mut self.merged, NextSlot::new());
+            }
+        }
+
+        let mut guard = Guard {
+            merged: Self::new(),
+            lhs: self,
+            rhs: other,
+        };
+        let mut next_cell = &mut guard.merged;
+        while let (Some(lhs), Some(rhs)) = (guard.lhs.get(), guard.rhs.get()) {
+            let take_rhs = compare(&rhs.val, &lhs.val) == Ordering::Less;
+            let src = if take_rhs {
+                &mut *guard.rhs
+            } else {
+                &mut *guard.lhs
+            };
+            let Some(node) = src.unlink_first(|_| true) else {
+                unreachable!("merge_by: src had value but unlink_first() returned None");
+            };
+            let _ = next_cell.set(node);
+            // Safe because we have just set the `next_cell` value.
+            let Some(inserted) = next_cell.get_mut() else {
+                unreachable!("merge_by: next_cell was just set but get_mut() returned None");
+            };
+            next_cell = &mut inserted.next;
+        }
+
+        // At most one of the chains has the remaining nodes, which are already sorted.
+        if let Some(rest) = guard.lhs.take().or_else(|| guard.rhs.take()) {
+            let _ = next_cell.set(rest);
+        }
+        // Both `lhs` and `rhs` are empty now, so the guard just moves the merged chain to `lhs`.
+    }
+
+    #[allow(clippy::type_complexity)]
     pub(crate) fn try_insert2(
         &self,
         value: Box<Cons<T, T, A>, A>,
@@ -71,6 +195,18 @@ impl<T: ?Sized, A: Allocator> NextSlot<T, A> {
     }
 }
 
+impl<T: ?Sized, A: Allocator> Drop for NextSlot<T, A> {
+    fn drop(&mut self) {
+        // Unlink the nodes one by one. Dropping the chain as is would recurse into each node's
+        // `next` slot, and overflow the stack for a long list.
+        let mut next_box = self.take();
+        while let Some(mut cur_box) = next_box {
+            next_box = cur_box.next.take();
+            // `cur_box` is dropped here with its empty `next` slot.
+        }
+    }
+}
+
 /// Cache mode for `OnceList` (e.g. tail cache, len cache).
 ///
 /// This trait is **sealed**: downstream crates cannot implement it.
@@ -83,8 +219,9 @@ pub trait CacheMode<T: ?Sized, A: Allocator>: sealed::Sealed + Clone {
 
     /// Returns a cached tail insertion slot, if available.
     ///
-    /// Returning `None` means the caller should fall back to scanning from the head.
-    fn tail_slot_opt<'a>(&'a self) -> Option<&'a NextSlot<T, A>> {
+    /// `head_slot` is the list's head slot. Returning `None` means the caller should fall back
+    /// to scanning from it.
+    fn tail_slot_opt<'a>(&'a self, _head_slot: &'a NextSlot<T, A>) -> Option<&'a NextSlot<T, A>> {
         None
     }
 
@@ -93,14 +230,30 @@ pub trait CacheMode<T: ?Sized, A: Allocator>: sealed::Sealed + Clone {
     /// Cache modes that track tail insertion slots and/or length should override this.
     fn on_push_success(&self, _next_slot: &NextSlot<T, A>) {}
 
+    /// Called after an extend successfully inserted a chain of `count` nodes at once.
+    ///
+    /// `next_slot` is the last inserted node's `next` slot.
+    fn on_extend_success(&self, _next_slot: &NextSlot<T, A>, _count: usize) {}
+
     /// Called after a remove successfully removed a node.
     fn on_remove_success(&self) {}
 
+    /// Called after walking the whole list to re-establish the caches.
+    ///
+    /// `next_slot` is the last node's `next` slot (`None` if the list is empty), and `len` is
+    /// the number of the nodes.
+    fn on_refresh(&self, _next_slot: Option<&NextSlot<T, A>>, _len: usize) {}
+
     /// Called when the list is cleared.
     fn on_clear(&self) {}
 
     /// Called when list structure may change via `&mut self` methods (e.g. remove).
     ///
+    /// This is also called before handing out mutable references into the nodes (e.g.
+    /// `iter_mut`), even though the structure does not change. With `std`'s `Box` (the
+    /// `nightly` feature), a `&mut` into a node is a unique reborrow of its box, and under
+    /// Stacked Borrows it invalidates any raw pointer previously derived into that node.
+    ///
     /// Implementations should drop any cached pointers/slots that could become stale.
     fn on_structure_change(&self);
 }
@@ -127,6 +280,26 @@ pub struct WithTail<T: ?Sized, A: Allocator> {
 
 type SlotPtr<T, A> = NonNull<NextSlot<T, A>>;
 
+/// Resolves a cached tail slot pointer, returning it only if the slot is still empty.
+///
+/// The first node's box is owned by `head_slot`, so moving the list retags it, and a pointer
+/// cached into that node may no longer be usable. Such a slot is re-derived from `head_slot`
+/// by comparing addresses, without dereferencing the cached pointer.
+fn cached_tail_slot<T: ?Sized, A: Allocator>(
+    cached: Option<SlotPtr<T, A>>,
+    head_slot: &NextSlot<T, A>,
+) -> Option<&NextSlot<T, A>> {
+    let p = cached?;
+    let slot = match head_slot.get() {
+        Some(head) if ::std::ptr::eq(p.as_ptr(), &head.next) => &head.next,
+        // Safe because every other cached slot lives in a node owned by another node's box,
+        // which is not retagged when the list moves.
+        _ => unsafe { p.as_ref() },
+    };
+    // Fast-path: if the cached slot is still empty, use it.
+    slot.get().is_none().then_some(slot)
+}
+
 impl<T: ?Sized, A: Allocator> Clone for WithTail<T, A> {
     fn clone(&self) -> Self {
         // Do NOT clone the pointer; it would point into the other list.
@@ -137,21 +310,22 @@ impl<T: ?Sized, A: Allocator> Clone for WithTail<T, A> {
 impl<T: ?Sized, A: Allocator> sealed::Sealed for WithTail<T, A> {}
 
 impl<T: ?Sized, A: Allocator> CacheMode<T, A> for WithTail<T, A> {
-    fn tail_slot_opt<'a>(&'a self) -> Option<&'a NextSlot<T, A>> {
-        if let Some(p) = self.next_slot.get() {
-            let slot = unsafe { p.as_ref() };
-            // Fast-path: if the cached slot is still empty, use it.
-            if slot.get().is_none() {
-                return Some(slot);
-            }
-        }
-        None
+    fn tail_slot_opt<'a>(&'a self, head_slot: &'a NextSlot<T, A>) -> Option<&'a NextSlot<T, A>> {
+        cached_tail_slot(self.next_slot.get(), head_slot)
     }
 
     fn on_push_success(&self, next_slot: &NextSlot<T, A>) {
         self.next_slot.set(Some(NonNull::from(next_slot)));
     }
 
+    fn on_extend_success(&self, next_slot: &NextSlot<T, A>, _count: usize) {
+        self.next_slot.set(Some(NonNull::from(next_slot)));
+    }
+
+    fn on_refresh(&self, next_slot: Option<&NextSlot<T, A>>, _len: usize) {
+        self.next_slot.set(next_slot.map(NonNull::from));
+    }
+
     fn on_structure_change(&self) {
         self.next_slot.set(None);
     }
@@ -197,6 +371,14 @@ impl<T: ?Sized, A: Allocator> CacheMode<T, A> for WithLen<T, A> {
         self.len.set(self.len.get() + 1);
     }
 
+    fn on_extend_success(&self, _next_slot: &NextSlot<T, A>, count: usize) {
+        self.len.set(self.len.get() + count);
+    }
+
+    fn on_refresh(&self, _next_slot: Option<&NextSlot<T, A>>, len: usize) {
+        self.len.set(len);
+    }
+
     fn on_remove_success(&self) {
         self.len.set(self.len.get() - 1);
     }
@@ -249,14 +431,8 @@ impl<T: ?Sized, A: Allocator> CacheMode<T, A> for WithTailLen<T, A> {
         Some(self.len.get())
     }
 
-    fn tail_slot_opt<'a>(&'a self) -> Option<&'a NextSlot<T, A>> {
-        if let Some(p) = self.next_slot.get() {
-            let slot = unsafe { p.as_ref() };
-            if slot.get().is_none() {
-                return Some(slot);
-            }
-        }
-        None
+    fn tail_slot_opt<'a>(&'a self, head_slot: &'a NextSlot<T, A>) -> Option<&'a NextSlot<T, A>> {
+        cached_tail_slot(self.next_slot.get(), head_slot)
     }
 
     fn on_push_success(&self, next_slot: &NextSlot<T, A>) {
@@ -264,6 +440,16 @@ impl<T: ?Sized, A: Allocator> CacheMode<T, A> for WithTailLen<T, A> {
         self.next_slot.set(Some(NonNull::from(next_slot)));
     }
 
+    fn on_extend_success(&self, next_slot: &NextSlot<T, A>, count: usize) {
+        self.len.set(self.len.get() + count);
+        self.next_slot.set(Some(NonNull::from(next_slot)));
+    }
+
+    fn on_refresh(&self, next_slot: Option<&NextSlot<T, A>>, len: usize) {
+        self.len.set(len);
+        self.next_slot.set(next_slot.map(NonNull::from));
+    }
+
     fn on_remove_success(&self) {
         self.len.set(self.len.get() - 1);
     }
@@ -293,3 +479,29 @@ impl<T: ?Sized, A: Allocator> Default for WithTailLen<T, A> {
         Self::new()
     }
 }
+
+/// Maps a cache mode for the value type `T` and the allocator `A` to the same cache mode for
+/// another value type `U` and another allocator `B`.
+///
+/// This trait is **sealed**: downstream crates cannot implement it.
+#[doc(hidden)]
+pub trait RebindAlloc<U: ?Sized, B: Allocator>: sealed::Sealed {
+    /// The same cache mode for the value type `U` and the allocator `B`.
+    type Rebound: CacheMode<U, B> + Default;
+}
+
+impl<U: ?Sized, B: Allocator> RebindAlloc<U, B> for NoCache {
+    type Rebound = NoCache;
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator, B: Allocator> RebindAlloc<U, B> for WithTail<T, A> {
+    type Rebound = WithTail<U, B>;
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator, B: Allocator> RebindAlloc<U, B> for WithLen<T, A> {
+    type Rebound = WithLen<U, B>;
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator, B: Allocator> RebindAlloc<U, B> for WithTailLen<T, A> {
+    type Rebound = WithTailLen<U, B>;
+}
@@ -12,11 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ::allocator_api2::alloc::Allocator;
-#[cfg(feature = "nightly")]
+use ::allocator_api2::alloc::{Allocator, Layout};
 use ::allocator_api2::boxed::Box;
 #[cfg(feature = "nightly")]
 use ::std::marker::Unsize;
+use ::std::ptr::NonNull;
 
 use crate::cache_mode::NextSlot;
 
@@ -38,77 +38,353 @@ use crate::cache_mode::NextSlot;
 ///
 /// Separating `T` and `U` lets us safely treat:
 /// - `&Cons<SizedT, U, A>` as `&Cons<UnsizedT, U, A>` (coercing only the current `val`),
-/// while keeping the tail (`next`) layout unchanged.
-#[derive(Clone)]
+///   while keeping the tail (`next`) layout unchanged.
+///
+/// ## Why `#[repr(C)]`?
+///
+/// The unsafe code building a cons around an existing unsized value (e.g.
+/// [`Cons::new_boxed_from_box`]) computes the cons layout as the layout of the fields before `val`
+/// extended by the value's layout. `#[repr(C)]` guarantees that this is the actual layout of the
+/// struct.
+#[repr(C)]
 pub(crate) struct Cons<T: ?Sized, U: ?Sized, A: Allocator> {
     pub(crate) next: NextSlot<U, A>,
+    /// A process-wide unique number of this node, to detect a stale [`crate::raw::NodeId`] whose
+    /// address has been reused by a newer node. Only present in debug builds.
+    #[cfg(debug_assertions)]
+    pub(crate) serial: usize,
     pub(crate) val: T,
 }
 
+/// Returns a new node serial number. See [`Cons::serial`].
+#[cfg(debug_assertions)]
+fn next_serial() -> usize {
+    use ::std::sync::atomic::{AtomicUsize, Ordering};
+    static NEXT_SERIAL: AtomicUsize = AtomicUsize::new(0);
+    NEXT_SERIAL.fetch_add(1, Ordering::Relaxed)
+}
+
 impl<T, U: ?Sized, A: Allocator> Cons<T, U, A> {
     pub(crate) fn new(val: T) -> Self {
         Self {
             next: NextSlot::new(),
+            #[cfg(debug_assertions)]
+            serial: next_serial(),
             val,
         }
     }
 }
 
-#[cfg(feature = "nightly")]
-impl<T: ?Sized, A: Allocator> Cons<T, T, A> {
-    pub(crate) fn new_boxed<U>(val: U, alloc: A) -> Box<Self, A>
+impl<T: ?Sized, U: ?Sized, A: Allocator> Cons<T, U, A> {
+    /// Returns the layout of the fields before `val`, padded up to the struct's alignment only.
+    ///
+    /// Extending it by the value's layout gives the layout of the whole cons.
+    fn header_layout() -> Layout {
+        let header = Layout::new::<Cons<(), U, A>>();
+        let val_offset = ::std::mem::offset_of!(Cons<(), U, A>, val);
+        match Layout::from_size_align(val_offset, header.align()) {
+            Ok(layout) => layout,
+            Err(_) => unreachable!("header_layout: the offset is within a valid layout"),
+        }
+    }
+
+    /// Initializes the fields before `val` of a newly allocated cons.
+    ///
+    /// # Safety
+    /// `cons_ptr` must be valid for writes.
+    unsafe fn write_header(cons_ptr: *mut Self) {
+        unsafe {
+            ::std::ptr::addr_of_mut!((*cons_ptr).next).write(NextSlot::new());
+            #[cfg(debug_assertions)]
+            ::std::ptr::addr_of_mut!((*cons_ptr).serial).write(next_serial());
+        }
+    }
+}
+
+impl<E, U: ?Sized, A: Allocator> Cons<[E], U, A> {
+    /// Returns the memory layout of a cons whose value is a slice of `len` elements.
+    pub(crate) fn layout_for_len(len: usize) -> Layout {
+        match Layout::array::<E>(len).and_then(|val| Self::header_layout().extend(val)) {
+            Ok((layout, _)) => layout.pad_to_align(),
+            Err(_) => panic!("capacity overflow"),
+        }
+    }
+
+    /// Allocates a new boxed cons holding a copy of the given slice.
+    pub(crate) fn new_boxed_slice_copy(src: &[E], alloc: A) -> Box<Self, A>
     where
-        U: Unsize<T>,
+        E: Copy,
     {
-        // As mentioned in the [`Cons`]'s document, this unsized coercion cast is safe!
-        Box::<Cons<U, T, A>, A>::new_in(
-            Cons::<U, T, A> {
-                next: NextSlot::new(),
-                val,
-            },
-            alloc,
-        )
+        let layout = Self::layout_for_len(src.len());
+        let ptr = match alloc.allocate(layout) {
+            Ok(ptr) => ptr,
+            Err(_) => ::std::alloc::handle_alloc_error(layout),
+        };
+        // Build the fat pointer from the thin pointer and the slice length.
+        let cons_ptr =
+            ::std::ptr::slice_from_raw_parts_mut(ptr.cast::<E>().as_ptr(), src.len()) as *mut Self;
+        unsafe {
+            Self::write_header(cons_ptr);
+            ::std::ptr::addr_of_mut!((*cons_ptr).val)
+                .cast::<E>()
+                .copy_from_nonoverlapping(src.as_ptr(), src.len());
+            Box::from_raw_in(cons_ptr, alloc)
+        }
     }
 
-    pub(crate) fn box_into_inner_box(self: Box<Self, A>) -> Box<T, A> {
-        use ::allocator_api2::alloc;
-        use ::std::ptr::{metadata, NonNull};
+    /// Allocates a new boxed cons holding a clone of each element of the given slice.
+    ///
+    /// If an element's `clone()` panics, the already cloned elements are dropped and the
+    /// allocation is freed.
+    pub(crate) fn new_boxed_slice_clone(src: &[E], alloc: A) -> Box<Self, A>
+    where
+        E: Clone,
+    {
+        /// Cleans up the partially initialized cons on panic.
+        struct Guard<'a, E, U: ?Sized, A: Allocator> {
+            cons_ptr: *mut Cons<[E], U, A>,
+            layout: Layout,
+            alloc: &'a A,
+            initialized: usize,
+        }
 
-        let cons_layout = alloc::Layout::for_value::<Cons<T, T, A>>(&self);
-        let layout = alloc::Layout::for_value::<T>(&self.val);
-        let metadata = metadata(&self.val);
-        let (raw_cons, alloc) = Box::into_raw_with_allocator(self);
-        let dst = match alloc.allocate(layout) {
-            Ok(dst) => dst,
+        impl<E, U: ?Sized, A: Allocator> Drop for Guard<'_, E, U, A> {
+            fn drop(&mut self) {
+                unsafe {
+                    let val_ptr = ::std::ptr::addr_of_mut!((*self.cons_ptr).val).cast::<E>();
+                    ::std::ptr::drop_in_place(::std::ptr::slice_from_raw_parts_mut(
+                        val_ptr,
+                        self.initialized,
+                    ));
+                    ::std::ptr::drop_in_place(::std::ptr::addr_of_mut!((*self.cons_ptr).next));
+                    let cons_ptr = NonNull::new_unchecked(self.cons_ptr.cast::<u8>());
+                    self.alloc.deallocate(cons_ptr, self.layout);
+                }
+            }
+        }
+
+        let layout = Self::layout_for_len(src.len());
+        let ptr = match alloc.allocate(layout) {
+            Ok(ptr) => ptr,
             Err(_) => ::std::alloc::handle_alloc_error(layout),
         };
+        // Build the fat pointer from the thin pointer and the slice length.
+        let cons_ptr =
+            ::std::ptr::slice_from_raw_parts_mut(ptr.cast::<E>().as_ptr(), src.len()) as *mut Self;
+        unsafe { Self::write_header(cons_ptr) };
+
+        let mut guard = Guard {
+            cons_ptr,
+            layout,
+            alloc: &alloc,
+            initialized: 0,
+        };
+        let val_ptr = unsafe { ::std::ptr::addr_of_mut!((*cons_ptr).val).cast::<E>() };
+        for (i, elem) in src.iter().enumerate() {
+            unsafe { val_ptr.add(i).write(elem.clone()) };
+            guard.initialized += 1;
+        }
+        ::std::mem::forget(guard);
+
+        unsafe { Box::from_raw_in(cons_ptr, alloc) }
+    }
+}
+
+impl<E, A: Allocator> Cons<[E], [E], A> {
+    /// Moves the slice value out of the boxed cons into a newly allocated boxed slice.
+    pub(crate) fn box_into_inner_slice(boxed: Box<Self, A>) -> Box<[E], A> {
+        let len = boxed.val.len();
+        let cons_layout = Layout::for_value::<Self>(&boxed);
+        let layout = match Layout::array::<E>(len) {
+            Ok(layout) => layout,
+            Err(_) => unreachable!("the slice was already allocated with this layout"),
+        };
+        let (raw_cons, alloc) = Box::into_raw_with_allocator(boxed);
+        // A zero-sized box must not be allocated (and will not be deallocated) by `Box`.
+        let dst = if layout.size() == 0 {
+            NonNull::<E>::dangling()
+        } else {
+            match alloc.allocate(layout) {
+                Ok(dst) => dst.cast::<E>(),
+                Err(_) => ::std::alloc::handle_alloc_error(layout),
+            }
+        };
+
+        unsafe {
+            // Do memcpy.
+            ::std::ptr::copy_nonoverlapping(
+                ::std::ptr::addr_of!((*raw_cons).val).cast::<E>(),
+                dst.as_ptr(),
+                len,
+            );
+            Self::free_without_val(raw_cons, cons_layout, &alloc);
+            Box::from_raw_in(
+                ::std::ptr::slice_from_raw_parts_mut(dst.as_ptr(), len),
+                alloc,
+            )
+        }
+    }
 
-        // Make sure to drop the `cons`'s unused fields.
-        let Cons { next, val } = unsafe { &*raw_cons };
-        let _ = unsafe { ::std::ptr::read(next) };
-        let raw_src = val as *const T;
+    /// Moves the slice value out of the boxed cons as an array.
+    ///
+    /// The caller must make sure the slice length is exactly `N`.
+    pub(crate) fn box_into_array<const N: usize>(boxed: Box<Self, A>) -> [E; N] {
+        assert_eq!(boxed.val.len(), N);
+        // Safe because we have just checked the length.
+        unsafe { Self::box_into_inner_as::<[E; N]>(boxed) }
+    }
+}
 
-        // Do memcpy.
+impl<T: ?Sized, A: Allocator> Cons<T, T, A> {
+    /// Moves the boxed value into a newly allocated boxed cons.
+    ///
+    /// The value is moved by a memcpy into the new allocation, and the old allocation is freed
+    /// by its own allocator without dropping the value.
+    pub(crate) fn new_boxed_from_box<B: Allocator>(val: Box<T, B>, alloc: A) -> Box<Self, A> {
+        let val_layout = Layout::for_value::<T>(&val);
+        let cons_layout = match Self::header_layout().extend(val_layout) {
+            Ok((layout, _)) => layout.pad_to_align(),
+            Err(_) => panic!("capacity overflow"),
+        };
+        let dst = match alloc.allocate(cons_layout) {
+            Ok(dst) => dst,
+            Err(_) => ::std::alloc::handle_alloc_error(cons_layout),
+        };
+
+        let (raw_val, val_alloc) = Box::into_raw_with_allocator(val);
+        // Re-point the value's fat pointer to the new allocation, keeping the metadata.
+        let raw_cons = with_data_ptr(raw_val as *mut Self, dst.cast::<u8>().as_ptr());
         unsafe {
+            Self::write_header(raw_cons);
+            // Do memcpy.
             ::std::ptr::copy_nonoverlapping(
-                raw_src.cast::<u8>(),
-                dst.cast::<u8>().as_ptr(),
-                layout.size(),
+                raw_val.cast::<u8>(),
+                ::std::ptr::addr_of_mut!((*raw_cons).val).cast::<u8>(),
+                val_layout.size(),
             );
         }
 
-        // Free the `cons`'s memory. Not `drop` because we already dropped the fields.
+        // Free the old value's memory. Not `drop` because the value is moved.
+        if val_layout.size() != 0 {
+            unsafe {
+                let raw_val = match NonNull::new(raw_val.cast::<u8>()) {
+                    Some(p) => p,
+                    None => unreachable!("Box::into_raw_with_allocator returned null"),
+                };
+                val_alloc.deallocate(raw_val, val_layout);
+            }
+        }
+
+        unsafe { Box::from_raw_in(raw_cons, alloc) }
+    }
+
+    /// Moves the value out of the boxed cons as the sized type `U`, and frees the cons.
+    ///
+    /// # Safety
+    /// The value must be a valid `U` at the same address, e.g. `T` is `dyn Any` whose concrete
+    /// type is `U`, or `T` is `[E]` whose length matches the array type `U`.
+    pub(crate) unsafe fn box_into_inner_as<U>(boxed: Box<Self, A>) -> U {
+        let cons_layout = Layout::for_value::<Self>(&boxed);
+        let (raw_cons, alloc) = Box::into_raw_with_allocator(boxed);
+        unsafe {
+            let val = ::std::ptr::read(::std::ptr::addr_of!((*raw_cons).val).cast::<U>());
+            Self::free_without_val(raw_cons, cons_layout, &alloc);
+            val
+        }
+    }
+
+    /// Drops the `next` field and frees the cons memory, without dropping the value.
+    ///
+    /// The cons layout must be computed by the caller before moving the value out, so that no
+    /// reference to the partially moved cons is created here.
+    ///
+    /// # Safety
+    /// `raw_cons` must come from `Box::into_raw_with_allocator` with the allocator `alloc`, and
+    /// the value must have been moved out already.
+    unsafe fn free_without_val(raw_cons: *mut Self, cons_layout: Layout, alloc: &A) {
         unsafe {
+            ::std::ptr::drop_in_place(::std::ptr::addr_of_mut!((*raw_cons).next));
             let raw_cons = match NonNull::new(raw_cons) {
                 Some(p) => p,
                 None => unreachable!("Box::into_raw_with_allocator returned null"),
             };
             alloc.deallocate(raw_cons.cast(), cons_layout);
         }
+    }
+
+    /// Moves the value out of the boxed cons into a newly allocated box.
+    pub(crate) fn box_into_inner_box(boxed: Box<Self, A>) -> Box<T, A> {
+        let cons_layout = Layout::for_value::<Self>(&boxed);
+        let layout = Layout::for_value::<T>(&boxed.val);
+        let (raw_cons, alloc) = Box::into_raw_with_allocator(boxed);
+        // A zero-sized box must not be allocated (and will not be deallocated) by `Box`.
+        let dst = if layout.size() == 0 {
+            ::std::ptr::without_provenance_mut::<u8>(layout.align())
+        } else {
+            match alloc.allocate(layout) {
+                Ok(dst) => dst.cast::<u8>().as_ptr(),
+                Err(_) => ::std::alloc::handle_alloc_error(layout),
+            }
+        };
+
+        unsafe {
+            let raw_src = ::std::ptr::addr_of_mut!((*raw_cons).val);
+
+            // Do memcpy.
+            ::std::ptr::copy_nonoverlapping(raw_src.cast::<u8>(), dst, layout.size());
 
-        // Create a new fat pointer for dst by combining the thin pointer and the metadata.
-        let dst = NonNull::<T>::from_raw_parts(dst.cast::<u8>(), metadata);
+            Self::free_without_val(raw_cons, cons_layout, &alloc);
 
-        unsafe { Box::from_non_null_in(dst, alloc) }
+            // Create a new fat pointer for dst by combining the thin pointer and the metadata.
+            Box::from_raw_in(with_data_ptr(raw_src, dst), alloc)
+        }
+    }
+}
+
+/// Replaces the data pointer (the address and its provenance) of a possibly-fat pointer, while
+/// keeping its metadata (e.g. the slice length or the vtable).
+fn with_data_ptr<T: ?Sized>(ptr: *mut T, data: *mut u8) -> *mut T {
+    #[cfg(feature = "nightly")]
+    {
+        ::std::ptr::from_raw_parts_mut(data, ::std::ptr::metadata(ptr))
+    }
+    #[cfg(not(feature = "nightly"))]
+    {
+        // Stable Rust has no API to combine a data pointer and the metadata of a generic
+        // `T: ?Sized`. `ptr.with_addr()` keeps the metadata, but it also keeps the provenance of
+        // `ptr`'s allocation, which must not be used to access the `data` allocation.
+        //
+        // So this overwrites the data pointer in place, assuming that it is stored at the start
+        // of the pointer, and then checks that assumption: the callers always pass a `data`
+        // address different from `ptr`'s, so the address changes only if the right word has
+        // been overwritten. The assumption holds on every current target, and if it ever does
+        // not, this panics instead of returning a pointer with a broken metadata.
+        let mut new_ptr = ptr;
+        unsafe { (&mut new_ptr as *mut *mut T).cast::<*mut u8>().write(data) };
+        assert_eq!(
+            new_ptr.cast::<u8>(),
+            data,
+            "with_data_ptr: the data pointer is not stored at the start of the pointer"
+        );
+        new_ptr
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T: ?Sized, A: Allocator> Cons<T, T, A> {
+    pub(crate) fn new_boxed<U>(val: U, alloc: A) -> Box<Self, A>
+    where
+        U: Unsize<T>,
+    {
+        // As mentioned in the [`Cons`]'s document, this unsized coercion cast is safe!
+        Box::<Cons<U, T, A>, A>::new_in(
+            Cons::<U, T, A> {
+                next: NextSlot::new(),
+                #[cfg(debug_assertions)]
+                serial: next_serial(),
+                val,
+            },
+            alloc,
+        )
     }
 }
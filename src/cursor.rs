@@ -0,0 +1,427 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::allocator_api2::alloc::{Allocator, Global};
+use ::allocator_api2::boxed::Box;
+use ::std::fmt::{self, Debug};
+
+use crate::cache_mode::{CacheMode, NextSlot, NoCache};
+use crate::cons::Cons;
+use crate::once_list::OnceListCore;
+
+/// A cursor over a [`crate::OnceList`], which can pause and resume a traversal.
+///
+/// The cursor points to a position in the list, which is either a value or the end of the list.
+/// Like [`crate::Iter`], a cursor at the end observes newly pushed values: after a push,
+/// [`Cursor::current`] returns the pushed value.
+pub struct Cursor<'a, T: ?Sized, A: Allocator = Global> {
+    /// The slot holding the current node, i.e. the list's head slot or the previous node's
+    /// `next` slot.
+    slot: &'a NextSlot<T, A>,
+    index: usize,
+}
+
+impl<'a, T: ?Sized, A: Allocator> Cursor<'a, T, A> {
+    /// Returns the reference to the current value, or `None` if the cursor is at the end.
+    pub fn current(&self) -> Option<&'a T> {
+        self.slot.get().map(|cons| &cons.val)
+    }
+
+    /// Returns the reference to the value after the current one, or `None` if there is no such
+    /// value.
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.slot.get()?.next.get().map(|cons| &cons.val)
+    }
+
+    /// Moves the cursor to the next value, or to the end of the list.
+    ///
+    /// This has no effect if the cursor is already at the end.
+    pub fn move_next(&mut self) {
+        if let Some(cons) = self.slot.get() {
+            self.slot = &cons.next;
+            self.index += 1;
+        }
+    }
+
+    /// Returns the index of the current value. If the cursor is at the end, this is the length of
+    /// the list at the time the cursor has reached there.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Clone for Cursor<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Copy for Cursor<'_, T, A> {}
+
+impl<T: ?Sized + Debug, A: Allocator> Debug for Cursor<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("index", &self.index)
+            .field("current", &self.current())
+            .finish()
+    }
+}
+
+/// A mutable cursor over a [`crate::OnceList`], which can insert and remove values at the
+/// cursor position in O(1).
+///
+/// The cursor points to a position in the list, which is either a value or the end of the list.
+/// Creating the cursor invalidates the cached tail insertion slot of the list, and the cached
+/// length is kept up to date by the cursor's operations.
+pub struct CursorMut<'a, T: ?Sized, A: Allocator = Global, C = NoCache> {
+    /// The slot holding the current node, i.e. the list's head slot or the previous node's
+    /// `next` slot.
+    slot: &'a mut NextSlot<T, A>,
+    index: usize,
+    alloc: &'a A,
+    cache_mode: &'a C,
+}
+
+impl<'a, T: ?Sized, A: Allocator, C> CursorMut<'a, T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Returns the mutable reference to the current value, or `None` if the cursor is at the
+    /// end.
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.slot.get_mut().map(|cons| &mut cons.val)
+    }
+
+    /// Returns the mutable reference to the value after the current one, or `None` if there is
+    /// no such value.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.slot
+            .get_mut()?
+            .next
+            .get_mut()
+            .map(|cons| &mut cons.val)
+    }
+
+    /// Moves the cursor to the next value, or to the end of the list.
+    ///
+    /// This has no effect if the cursor is already at the end.
+    pub fn move_next(&mut self) {
+        let Some(cons) = self.slot.get_mut() else {
+            return;
+        };
+        // Need to cast the `self` lifetime to `&'a` to update the `self.slot`.
+        let cons = unsafe { &mut *::std::ptr::from_mut(cons.as_mut()) };
+        self.slot = &mut cons.next;
+        self.index += 1;
+    }
+
+    /// Returns the index of the current value. If the cursor is at the end, this is the length of
+    /// the list.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a read-only cursor pointing to the same position, which borrows this cursor.
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            slot: self.slot,
+            index: self.index,
+        }
+    }
+
+    /// Moves all the values of the other list right after the current value, keeping their
+    /// order. The cursor keeps pointing to the current value.
+    ///
+    /// If the cursor is at the end, the values are appended to the back of the list, and the
+    /// first of them becomes the current value.
+    ///
+    /// The nodes are relinked instead of reallocated. Finding the other list's last node is O(1)
+    /// if its tail is cached, and O(m) otherwise. If this list caches its length, counting the
+    /// moved values is O(1) if the other list caches its length too, and O(m) otherwise. Note
+    /// that the moved nodes stay allocated by the other list's allocator instance.
+    ///
+    /// ```rust
+    /// use once_list2::{OnceList, OnceListWithTailLen};
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 4]);
+    /// let other = OnceListWithTailLen::<i32>::new();
+    /// other.extend([2, 3]);
+    ///
+    /// list.cursor_front_mut().splice_after(other);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    /// ```
+    pub fn splice_after<C2>(&mut self, mut other: OnceListCore<T, A, C2>)
+    where
+        C2: CacheMode<T, A>,
+    {
+        if other.head_slot.get().is_none() {
+            return;
+        }
+        // The number of the moved values is only needed to update the cached length.
+        let count = if self.cache_mode.cached_len().is_some() {
+            other.len()
+        } else {
+            0
+        };
+        let mut tail = other
+            .cache_mode
+            .tail_slot_opt(&other.head_slot)
+            .unwrap_or(&other.head_slot);
+        while let Some(next_box) = tail.get() {
+            tail = &next_box.next;
+        }
+
+        let slot = self.slot_after();
+        if let Some(rest) = slot.take() {
+            // Link the rest while the other list still owns its nodes. Once its first node is
+            // moved out below, a reference into that node (e.g. the tail slot of a single-node
+            // list) must not be used anymore.
+            let _ = tail.set(rest);
+        }
+        let Some(first) = other.head_slot.take() else {
+            unreachable!("splice_after: head_slot had value but take() returned None");
+        };
+        let _ = slot.set(first);
+        self.on_insert(count);
+    }
+
+    /// Splits the list right after the current value, and returns the values after it as a new
+    /// list. The cursor keeps pointing to the current value, which is now the back value.
+    ///
+    /// If the cursor is at the end, the returned list is empty. Only the link after the current
+    /// value is cut, so this method is O(1). The new list has a clone of the list's allocator and
+    /// the same cache mode.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2, 3, 4]);
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_next();
+    /// let rest = cursor.split_after();
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(rest.len(), 2);
+    /// assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&3, &4]);
+    /// ```
+    pub fn split_after(&mut self) -> OnceListCore<T, A, C>
+    where
+        A: Clone,
+    {
+        let other = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(self.alloc),
+            cache_mode: self.cache_mode.clone(),
+        };
+        let kept = self.index + usize::from(self.slot.get().is_some());
+        let len = self.cache_mode.cached_len();
+        if let Some(rest) = self.slot_after().take() {
+            let _ = other.head_slot.set(rest);
+        }
+
+        // The tail slots of both lists are not cached, only the lengths are updated.
+        other.cache_mode.on_structure_change();
+        other
+            .cache_mode
+            .on_refresh(None, len.map_or(0, |n| n - kept));
+        self.cache_mode.on_refresh(None, kept);
+        other
+    }
+
+    /// Returns the slot right after the current value, i.e. the current node's `next` slot, or
+    /// the end slot if the cursor is at the end.
+    fn slot_after(&mut self) -> &mut NextSlot<T, A> {
+        if self.slot.get().is_none() {
+            return self.slot;
+        }
+        match self.slot.get_mut() {
+            Some(cons) => &mut cons.next,
+            None => unreachable!("slot_after: slot had value but get_mut() returned None"),
+        }
+    }
+
+    /// Updates the cached length after `count` values are inserted by the cursor.
+    fn on_insert(&self, count: usize) {
+        // The tail slot has been invalidated when the cursor is created.
+        let len = self.cache_mode.cached_len().map_or(0, |n| n + count);
+        self.cache_mode.on_refresh(None, len);
+    }
+}
+
+impl<'a, T, A: Allocator, C> CursorMut<'a, T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Inserts a value right after the current value. The cursor keeps pointing to the current
+    /// value.
+    ///
+    /// If the cursor is at the end, the value is appended to the back of the list, and becomes
+    /// the current value.
+    ///
+    /// This method is O(1).
+    pub fn insert_after(&mut self, val: T)
+    where
+        A: Clone,
+    {
+        let new_cons = Box::new_in(Cons::new(val), A::clone(self.alloc));
+        let slot = self.slot_after();
+        if let Some(rest) = slot.take() {
+            let _ = new_cons.next.set(rest);
+        }
+        let _ = slot.set(new_cons);
+        self.on_insert(1);
+    }
+
+    /// Removes the current value from the list, and returns it. The cursor moves to the next
+    /// value, keeping its index.
+    ///
+    /// Returns `None` if the cursor is at the end. This method is O(1).
+    pub fn remove_current(&mut self) -> Option<T> {
+        let mut removed = self.slot.take()?;
+        // reconnect the list
+        if let Some(next) = removed.next.take() {
+            let _ = self.slot.set(next);
+        }
+        self.cache_mode.on_remove_success();
+        Some(Box::into_inner(removed).val)
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
+    /// Returns a cursor pointing to the front value, or to the end if the list is empty.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2]);
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// assert_eq!((cursor.index(), cursor.current()), (0, Some(&1)));
+    /// assert_eq!(cursor.peek_next(), Some(&2));
+    /// cursor.move_next();
+    /// cursor.move_next();
+    /// assert_eq!((cursor.index(), cursor.current()), (2, None));
+    ///
+    /// // The cursor at the end observes the newly pushed value.
+    /// list.push(3);
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// ```
+    pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            slot: &self.head_slot,
+            index: 0,
+        }
+    }
+
+    /// Returns a cursor pointing to the back value, or to the end if the list is empty.
+    ///
+    /// This method is O(n) in any cache mode: the tail cache holds the next insertion slot, not
+    /// the back node, so the list is walked to find the back node like [`OnceListCore::back`].
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2]);
+    ///
+    /// let mut cursor = list.cursor_back();
+    /// assert_eq!((cursor.index(), cursor.current()), (1, Some(&2)));
+    /// list.push(3);
+    /// assert_eq!(cursor.peek_next(), Some(&3));
+    /// ```
+    pub fn cursor_back(&self) -> Cursor<'_, T, A> {
+        let mut slot = &self.head_slot;
+        let mut index = 0;
+        while let Some(cons) = slot.get() {
+            if cons.next.get().is_none() {
+                break;
+            }
+            slot = &cons.next;
+            index += 1;
+        }
+        Cursor { slot, index }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Returns a mutable cursor pointing to the front value, or to the end if the list is empty.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2, 4]);
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// assert_eq!(cursor.remove_current(), Some(1));
+    /// cursor.insert_after(3);
+    /// assert_eq!(cursor.peek_next(), Some(&mut 3));
+    ///
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&2, &3, &4]);
+    /// ```
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A, C> {
+        self.cache_mode.on_structure_change();
+        CursorMut {
+            slot: &mut self.head_slot,
+            index: 0,
+            alloc: &self.alloc,
+            cache_mode: &self.cache_mode,
+        }
+    }
+
+    /// Returns a mutable cursor pointing to the back value, or to the end if the list is empty.
+    ///
+    /// This method is O(n) in any cache mode. See [`OnceListCore::cursor_back`] for the details.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2, 4]);
+    ///
+    /// let mut cursor = list.cursor_back_mut();
+    /// *cursor.current().unwrap() = 3;
+    /// cursor.insert_after(4);
+    ///
+    /// assert_eq!(list.len(), 4);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    /// ```
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A, C> {
+        self.cache_mode.on_structure_change();
+        let mut slot = &mut self.head_slot;
+        let mut index = 0;
+        while slot.get().is_some_and(|cons| cons.next.get().is_some()) {
+            // Safe because we are sure the `slot` value is set.
+            let Some(cons) = slot.get_mut() else {
+                unreachable!("cursor_back_mut: slot had value but get_mut() returned None");
+            };
+            slot = &mut cons.next;
+            index += 1;
+        }
+        CursorMut {
+            slot,
+            index,
+            alloc: &self.alloc,
+            cache_mode: &self.cache_mode,
+        }
+    }
+}
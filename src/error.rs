@@ -0,0 +1,56 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::std::fmt::{self, Display};
+
+/// An error returned when an index is out of the list's range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexError {
+    /// The requested index.
+    pub index: usize,
+    /// The length of the list at the time of the access.
+    pub len: usize,
+}
+
+impl Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index out of range: the len is {} but the index is {}",
+            self.len, self.index
+        )
+    }
+}
+
+impl ::std::error::Error for IndexError {}
+
+/// An error returned when a node allocation fails in the middle of
+/// [`OnceListCore::try_extend`](crate::OnceListCore::try_extend).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TryExtendError {
+    /// The number of values appended to the list before the failure.
+    pub inserted: usize,
+}
+
+impl Display for TryExtendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "memory allocation failed after appending {} values",
+            self.inserted
+        )
+    }
+}
+
+impl ::std::error::Error for TryExtendError {}
@@ -16,7 +16,8 @@ use ::allocator_api2::alloc::Allocator;
 use ::allocator_api2::alloc::Global;
 use ::allocator_api2::boxed::Box;
 
-use crate::cache_mode::NextSlot;
+use crate::cache_mode::{NextSlot, NoCache};
+use crate::once_list::{OnceList, OnceListCore};
 
 /// An iterator over references in a [`crate::OnceList`].
 ///
@@ -65,19 +66,67 @@ impl<'a, T: ?Sized + 'a, A: Allocator> Iterator for IterMut<'a, T, A> {
     }
 }
 
-pub struct IntoIter<T, A: Allocator>(pub(crate) NextSlot<T, A>);
+/// A mutable iterator over references in a [`crate::OnceList`], in the back-to-front order.
+///
+/// Because the list is singly-linked, creating this iterator collects all the references into a
+/// buffer in a forward pass first. This is a one-time O(n) time and memory cost.
+pub struct IterMutRev<'a, T: ?Sized> {
+    pub(crate) refs: ::std::iter::Rev<::std::vec::IntoIter<&'a mut T>>,
+}
+
+impl<'a, T: ?Sized + 'a> Iterator for IterMutRev<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.refs.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.refs.size_hint()
+    }
+}
+
+pub struct IntoIter<T, A: Allocator> {
+    pub(crate) next_slot: NextSlot<T, A>,
+    pub(crate) alloc: A,
+}
 
 impl<T, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next_cell = self.0.take()?;
+        let next_cell = self.next_slot.take()?;
         let next_cons = Box::into_inner(next_cell);
-        self.0 = next_cons.next;
+        self.next_slot = next_cons.next;
         Some(next_cons.val)
     }
 }
 
+impl<T, A: Allocator> IntoIter<T, A> {
+    /// Converts the unconsumed rest of the values back into a list.
+    ///
+    /// The remaining nodes are reused as they are, so this method is O(1).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// let mut iter = list.into_iter();
+    /// assert_eq!(iter.next(), Some(1));
+    ///
+    /// let rest = iter.into_list();
+    /// assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&2, &3]);
+    /// ```
+    pub fn into_list(self) -> OnceList<T, A> {
+        OnceListCore {
+            head_slot: self.next_slot,
+            alloc: self.alloc,
+            cache_mode: NoCache,
+        }
+    }
+}
+
 impl<'a, T: ?Sized, A: Allocator> Iter<'a, T, A> {
     pub(crate) fn new(next_slot: &'a NextSlot<T, A>) -> Self {
         Self { next_slot }
@@ -89,3 +138,11 @@ impl<'a, T: ?Sized, A: Allocator> IterMut<'a, T, A> {
         Self { next_slot }
     }
 }
+
+impl<'a, T: ?Sized> IterMutRev<'a, T> {
+    pub(crate) fn new<A: Allocator>(iter_mut: IterMut<'a, T, A>) -> Self {
+        Self {
+            refs: iter_mut.collect::<Vec<_>>().into_iter().rev(),
+        }
+    }
+}
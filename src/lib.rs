@@ -27,19 +27,32 @@ pub(crate) use ::std::cell::OnceCell;
 pub(crate) use ::std::sync::OnceLock as OnceCell;
 
 mod any;
+#[cfg(feature = "bumpalo")]
+mod bump;
 mod cache_mode;
 mod cons;
+mod cursor;
+mod error;
 mod iter;
+mod listener;
 mod once_list;
 mod oncecell_ext;
+mod pinned;
+pub mod raw;
+mod slice;
+mod strings;
 
 pub use crate::cache_mode::{NoCache, WithLen, WithTail, WithTailLen};
-pub use crate::iter::{IntoIter, Iter, IterMut};
+pub use crate::cursor::{Cursor, CursorMut};
+pub use crate::error::{IndexError, TryExtendError};
+pub use crate::iter::{IntoIter, Iter, IterMut, IterMutRev};
 pub use crate::once_list::OnceList;
 pub use crate::once_list::OnceListCore;
 pub use crate::once_list::OnceListWithLen;
 pub use crate::once_list::OnceListWithTail;
 pub use crate::once_list::OnceListWithTailLen;
+pub use crate::pinned::PinnedOnceList;
+pub use crate::strings::Joined;
 
 #[cfg(test)]
 mod tests {
@@ -153,6 +166,38 @@ mod tests {
         assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
     });
 
+    test_all_i32_variants!(fn test_prepend_all(list) {
+        let mut list = list;
+        list.push_back(4);
+        list.prepend_all([1, 2, 3]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&4));
+
+        // Pushing after prepending still appends to the back.
+        list.push_back(5);
+        list.prepend_all([]);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    });
+
+    test_all_i32_variants!(fn test_prepend_all_panic(list) {
+        use ::std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut list = list;
+        list.push_back(1);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.prepend_all((10..).inspect(|&i| assert!(i < 12, "the third value")));
+        }));
+        assert!(result.is_err());
+
+        // The list and its caches are unchanged.
+        assert_eq!(list.len(), 1);
+        list.push_back(2);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2]);
+    });
+
     test_all_i32_variants!(fn test_clear(list) {
         let mut list = list;
         list.extend([1, 2, 3]);
@@ -162,6 +207,22 @@ mod tests {
         assert_eq!(list.iter().next(), None);
     });
 
+    test_all_i32_variants!(fn test_take(list) {
+        let mut list = list;
+        list.extend([1, 2, 3]);
+        let taken = list.take();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+
+        // Both lists keep working independently, including the moved caches.
+        list.push_back(4);
+        taken.push_back(5);
+        assert_eq!(list.len(), 1);
+        assert_eq!(taken.len(), 4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![4]);
+        assert_eq!(taken.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 5]);
+    });
+
     test_all_i32_variants!(fn test_front_back(list) {
         assert_eq!(list.front(), None);
         assert_eq!(list.back(), None);
@@ -179,6 +240,26 @@ mod tests {
         assert_eq!(list.last(), list.back());
     });
 
+    test_all_i32_variants!(fn test_split_first_mut(list) {
+        let mut list = list;
+        assert!(list.split_first_mut().is_none());
+
+        list.push_back(1);
+        let (first, mut rest) = list.split_first_mut().unwrap();
+        assert_eq!(*first, 1);
+        assert_eq!(rest.next(), None);
+
+        list.extend([2, 3]);
+        let (first, rest) = list.split_first_mut().unwrap();
+        for v in rest {
+            *v *= 10;
+            *first += 1;
+        }
+        list.push_back(4);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![3, 20, 30, 4]);
+    });
+
     test_all_i32_variants!(fn test_pop_front(list) {
         let mut list = list;
         assert_eq!(list.pop_front(), None);
@@ -205,142 +286,2376 @@ mod tests {
         assert!(!list.contains(&4));
     });
 
-    test_all_i32_variants!(fn test_remove(list) {
-        let mut list = list;
-        list.extend([1, 2, 3]);
-        assert_eq!(list.remove(|&v| v == 2), Some(2));
-        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+    test_all_i32_variants!(fn test_contains_subsequence(list) {
+        assert!(list.contains_subsequence(&[]));
+        assert!(!list.contains_subsequence(&[1]));
+
+        list.extend([1, 2, 3, 4]);
+        assert!(list.contains_subsequence(&[2, 3]));
+        assert!(list.contains_subsequence(&[1, 2, 3, 4]));
+        assert!(list.contains_subsequence(&[4]));
+        assert!(!list.contains_subsequence(&[2, 4]));
+        assert!(!list.contains_subsequence(&[3, 4, 5]));
+    });
+
+    test_all_i32_variants!(fn test_starts_with_ends_with(list) {
+        assert!(list.starts_with(&[]));
+        assert!(list.ends_with(&[]));
+        assert!(!list.starts_with(&[1]));
+        assert!(!list.ends_with(&[1]));
+
+        list.extend([1, 2, 3]);
+        assert!(list.starts_with(&[1, 2]));
+        assert!(list.starts_with(&[1, 2, 3]));
+        assert!(!list.starts_with(&[2, 3]));
+        assert!(!list.starts_with(&[1, 2, 3, 4]));
+
+        assert!(list.ends_with(&[2, 3]));
+        assert!(list.ends_with(&[1, 2, 3]));
+        assert!(!list.ends_with(&[1, 2]));
+        assert!(!list.ends_with(&[0, 1, 2, 3]));
+    });
+
+    test_all_i32_variants!(fn test_starts_with_ends_with_other(list) {
+        let other = OnceListWithLen::<i32>::new();
+        assert!(list.starts_with_other(&other));
+        assert!(list.ends_with_other(&other));
+
+        list.extend([1, 2, 3]);
+        other.extend([1, 2]);
+        assert!(list.starts_with_other(&other));
+        assert!(!list.ends_with_other(&other));
+        assert!(!other.starts_with_other(&list));
+        assert!(!other.ends_with_other(&list));
+
+        let suffix = OnceList::from([2, 3]);
+        assert!(list.ends_with_other(&suffix));
+        assert!(!list.starts_with_other(&suffix));
+        assert!(list.starts_with_other(&list));
+        assert!(list.ends_with_other(&list));
+    });
+
+    test_all_i32_variants!(fn test_drain_batches(list) {
+        let mut list = list;
+        assert_eq!(list.drain_batches(2).next(), None);
+
+        list.extend(1..=5);
+        let batches = list.drain_batches(2).collect::<Vec<_>>();
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+
+        // Dropping the iterator early leaves the rest in the list.
+        list.extend(1..=5);
+        assert_eq!(list.drain_batches(3).next(), Some(vec![1, 2, 3]));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&4, &5]);
+    });
+
+    test_all_i32_variants!(fn test_try_for_each(list) {
+        assert_eq!(list.try_for_each(|_| Err(())), Ok(()));
+
+        list.extend([1, 2, 3, 4]);
+        let mut seen = Vec::new();
+        let res = list.try_for_each(|&v| {
+            seen.push(v);
+            if v == 2 { Err(v * 10) } else { Ok(()) }
+        });
+        assert_eq!(res, Err(20));
+        assert_eq!(seen, vec![1, 2]);
+
+        let mut sum = 0;
+        assert_eq!(list.try_for_each(|&v| { sum += v; Ok::<_, ()>(()) }), Ok(()));
+        assert_eq!(sum, 10);
+    });
+
+    test_all_i32_variants!(fn test_inspect(list) {
+        let mut seen = Vec::new();
+        list.inspect(|&v| seen.push(v));
+        assert!(seen.is_empty());
+
+        list.extend([1, 2, 3]);
+        list.inspect(|&v| seen.push(v))
+            .inspect(|&v| seen.push(v * 10));
+        assert_eq!(seen, vec![1, 2, 3, 10, 20, 30]);
+    });
+
+    test_all_i32_variants!(fn test_for_each_adjacent_pair_mut(list) {
+        let mut list = list;
+        let mut pairs = Vec::new();
+        list.for_each_adjacent_pair_mut(|a, b| pairs.push((*a, *b)));
+        list.push_back(1);
+        list.for_each_adjacent_pair_mut(|a, b| pairs.push((*a, *b)));
+        assert!(pairs.is_empty());
+
+        list.extend([5, 3, 4, 2]);
+        // A single bubble sort pass moves the max value to the back.
+        list.for_each_adjacent_pair_mut(|prev, cur| {
+            if prev > cur {
+                ::std::mem::swap(prev, cur);
+            }
+        });
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3, &4, &2, &5]);
+
+        list.push_back(6);
+        assert_eq!(list.back(), Some(&6));
+    });
+
+    test_all_i32_variants!(fn test_try_get(list) {
+        assert_eq!(list.try_get(0), Err(IndexError { index: 0, len: 0 }));
+
+        list.extend([1, 2, 3]);
+        assert_eq!(list.try_get(0), Ok(&1));
+        assert_eq!(list.try_get(2), Ok(&3));
+        let err = list.try_get(5).unwrap_err();
+        assert_eq!(err, IndexError { index: 5, len: 3 });
+        assert_eq!(err.to_string(), "index out of range: the len is 3 but the index is 5");
+    });
+
+    test_all_i32_variants!(fn test_iter_from(list) {
+        assert_eq!(list.iter_from(0).next(), None);
+        assert_eq!(list.iter_from(3).next(), None);
+
+        list.extend(1..=5);
+        assert_eq!(list.iter_from(0).collect::<Vec<_>>(), vec![&1, &2, &3, &4, &5]);
+        assert_eq!(list.iter_from(2).collect::<Vec<_>>(), vec![&3, &4, &5]);
+        assert_eq!(list.iter_from(5).next(), None);
+        assert_eq!(list.iter_from(10).next(), None);
+    });
+
+    test_all_i32_variants!(fn test_nth_mut(list) {
+        let mut list = list;
+        assert_eq!(list.nth_mut(0), None);
+
+        list.extend([1, 2, 3]);
+        *list.nth_mut(1).unwrap() *= 10;
+        assert_eq!(list.nth_mut(3), None);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &20, &3]);
+
+        list.push_back(4);
+        assert_eq!(list.back(), Some(&4));
+    });
+
+    test_all_i32_variants!(fn test_collect_into(list) {
+        list.collect_into([1, 2])
+            .collect_into(Vec::new())
+            .collect_into(3..5);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    });
+
+    test_all_i32_variants!(fn test_iter_with_last(list) {
+        assert_eq!(list.iter_with_last().next(), None);
+
+        list.push_back(1);
+        assert_eq!(list.iter_with_last().collect::<Vec<_>>(), vec![(&1, true)]);
+
+        list.extend([2, 3]);
+        assert_eq!(
+            list.iter_with_last().collect::<Vec<_>>(),
+            vec![(&1, false), (&2, false), (&3, true)]
+        );
+    });
+
+    test_all_i32_variants!(fn test_extend_from_other(list) {
+        list.extend_from_other(&OnceList::<i32>::new());
+        assert!(list.is_empty());
+
+        let other = OnceListWithTailLen::<i32>::new();
+        other.extend([1, 2]);
+        list.extend_from_other(&other);
+        list.push_back(3);
+        // Extending from itself only appends the values existing at the time of the call.
+        list.extend_from_other(&list);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &1, &2, &3]);
+        assert_eq!(other.len(), 2);
+    });
+
+    test_all_i32_variants!(fn test_extend_cloned(list) {
+        let mut list = list;
+        list.extend_cloned(&[1, 2]);
+        Extend::extend(&mut list, &[3, 4]);
+        Extend::extend(&mut list, [5].iter());
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    });
+
+    test_all_i32_variants!(fn test_extend_from_slice(list) {
+        list.extend_from_slice(&[]);
+        assert!(list.is_empty());
+        list.extend_from_slice(&[1, 2]);
+        list.push_back(3);
+        list.extend_from_slice(&[4]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.back(), Some(&4));
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    });
+
+    test_all_i32_variants!(fn test_into_iter_into_list(list) {
+        list.extend([1, 2, 3, 4, 5]);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.by_ref().take(2).collect::<Vec<_>>(), vec![1, 2]);
+
+        let rest = iter.into_list();
+        assert_eq!(rest.len(), 3);
+        rest.push_back(6);
+        assert_eq!(rest.into_iter().collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+    });
+
+    test_all_i32_variants!(fn test_get_or_extend_with(list) {
+        // An empty initialization is retried by the next call.
+        assert_eq!(list.get_or_extend_with(Vec::new).next(), None);
+        assert!(list.is_empty());
+
+        assert!(list.get_or_extend_with(|| [1, 2, 3]).eq([1, 2, 3].iter()));
+        let mut called = false;
+        let iter = list.get_or_extend_with(|| {
+            called = true;
+            [4]
+        });
+        assert!(iter.eq([1, 2, 3].iter()));
+        assert!(!called);
+        assert_eq!(list.len(), 3);
+
+        list.push_back(4);
+        assert_eq!(list.back(), Some(&4));
+    });
+
+    test_all_i32_variants!(fn test_first_or_push(list) {
+        assert_eq!(list.first_or_push(|| 1), &1);
+        let mut called = false;
+        assert_eq!(
+            list.first_or_push(|| {
+                called = true;
+                2
+            }),
+            &1
+        );
+        assert!(!called);
+        assert_eq!(list.len(), 1);
+
+        list.push_back(3);
+        assert_eq!(list.first_or_push(|| 4), &1);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+    });
+
+    test_all_i32_variants!(fn test_push_unique(list) {
+        let first = list.push_unique(1) as *const i32;
+        list.push_unique(2);
+        assert_eq!(list.push_unique(1) as *const i32, first);
+        list.push_unique(3);
+        list.push_unique(2);
+        assert_eq!(list.len(), 3);
+
+        list.push_back(1);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &1]);
+    });
+
+    test_all_i32_variants!(fn test_find_or_push(list) {
+        assert_eq!(list.find_or_push(|v| v % 2 == 0, || 2), &2);
+        assert_eq!(list.find_or_push(|v| v % 2 == 1, || 3), &3);
+        let mut called = false;
+        let found = list.find_or_push(
+            |&v| v > 1,
+            || {
+                called = true;
+                10
+            },
+        );
+        assert_eq!(found, &2);
+        assert!(!called);
+        assert_eq!(list.len(), 2);
+
+        list.push_back(4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&2, &3, &4]);
+    });
+
+    test_all_i32_variants!(fn test_update_or_push(list) {
+        let mut list = list;
+        assert!(!list.update_or_push(|&v| v == 1, |v| *v += 10, || 1));
+        assert!(!list.update_or_push(|&v| v == 2, |v| *v += 10, || 2));
+        assert!(list.update_or_push(|&v| v == 1, |v| *v += 10, || 1));
+        assert_eq!(list.len(), 2);
+
+        // Pushing after both paths must still work.
+        list.push_back(3);
+        assert!(!list.update_or_push(|&v| v == 4, |v| *v += 10, || 4));
+        list.push_back(5);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 2, 3, 4, 5]);
+    });
+
+    test_all_i32_variants!(fn test_drain_filter_into(list) {
+        let mut list = list;
+        let dest = OnceListWithTailLen::<i32>::new();
+        list.drain_filter_into(|_| true, &dest);
+        assert!(dest.is_empty());
+
+        list.extend(1..=7);
+        dest.push_back(0);
+        let first = list.iter().nth(1).unwrap() as *const i32;
+        list.drain_filter_into(|v| v % 2 == 0, &dest);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3, &5, &7]);
+        assert_eq!(dest.len(), 4);
+        assert_eq!(dest.iter().collect::<Vec<_>>(), vec![&0, &2, &4, &6]);
+        // The node is moved, not the value.
+        assert_eq!(dest.iter().nth(1).unwrap() as *const i32, first);
+
+        list.push_back(9);
+        dest.push_back(8);
+        assert_eq!(list.back(), Some(&9));
+        assert_eq!(dest.back(), Some(&8));
+    });
+
+    test_all_i32_variants!(fn test_remove(list) {
+        let mut list = list;
+        list.extend([1, 2, 3]);
+        assert_eq!(list.remove(|&v| v == 2), Some(2));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+
+        assert_eq!(list.remove(|&v| v == 0), None);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+
+        assert_eq!(list.remove(|&v| v == 1), Some(1));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3]);
+
+        assert_eq!(list.remove(|&v| v == 3), Some(3));
+        assert!(list.is_empty());
+    });
+
+    test_all_i32_variants!(fn test_dedup_by_key(list) {
+        let mut list = list;
+        list.dedup_by_key(|v| *v % 10);
+        assert!(list.is_empty());
+
+        list.extend([11, 21, 32]);
+        list.dedup_by_key(|v| *v % 10);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&11, &32]);
+
+        // The removed values were at the back, so pushing must still work.
+        list.extend([42, 3, 13, 23]);
+        list.dedup_by_key(|v| *v % 10);
+        assert_eq!(list.len(), 3);
+        list.push_back(4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 32, 3, 4]);
+    });
+
+    test_all_i32_variants!(fn test_retain(list) {
+        let mut list = list;
+        list.retain(|_| false);
+        assert!(list.is_empty());
+
+        list.extend(1..=10);
+        list.retain(|v| v % 3 != 1);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&2, &3, &5, &6, &8, &9]);
+
+        // The last value is removed, so pushing must still work.
+        list.retain(|&v| v != 9);
+        list.push_back(11);
+        assert_eq!(list.len(), 6);
+
+        list.retain_mut(|v| {
+            *v *= 10;
+            *v > 50
+        });
+        assert_eq!(list.len(), 3);
+        list.push_back(1);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![60, 80, 110, 1]);
+    });
+
+    test_all_i32_variants!(fn test_extract_if(list) {
+        let mut list = list;
+        assert_eq!(list.extract_if(|_| true).next(), None);
+
+        list.extend(1..=10);
+        let mut iter = list.extract_if(|v| v % 3 == 0);
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), Some(6));
+        drop(iter);
+        // The values after the last yielded one are untouched.
+        assert_eq!(list.len(), 8);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            vec![&1, &2, &4, &5, &7, &8, &9, &10]
+        );
+
+        // Extracting the back value, and then pushing must still work.
+        assert_eq!(list.extract_if(|&v| v >= 9).collect::<Vec<_>>(), vec![9, 10]);
+        list.push_back(11);
+        assert_eq!(list.len(), 7);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 4, 5, 7, 8, 11]);
+    });
+
+    test_all_i32_variants!(fn test_drain_while(list) {
+        let mut list = list;
+        assert_eq!(list.drain_while(|_| true).next(), None);
+
+        list.extend([1, 2, 3, 1, 2]);
+        assert_eq!(list.drain_while(|&v| v < 3).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(list.len(), 3);
+
+        // Dropping the iterator early keeps the rest of the values.
+        assert_eq!(list.drain_while(|_| true).next(), Some(3));
+        assert_eq!(list.len(), 2);
+
+        list.push_back(4);
+        assert_eq!(list.drain_while(|_| true).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert!(list.is_empty());
+        list.push_back(5);
+        assert_eq!(list.len(), 1);
+    });
+
+    test_all_i32_variants!(fn test_drain(list) {
+        let mut list = list;
+        assert_eq!(list.drain().next(), None);
+
+        list.extend([1, 2, 3]);
+        assert_eq!(list.drain().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+
+        // The list is reusable after draining, even if the iterator is dropped early.
+        list.extend([4, 5, 6]);
+        assert_eq!(list.drain().next(), Some(4));
+        assert!(list.is_empty());
+        list.push_back(7);
+        list.push_back(8);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![7, 8]);
+    });
+
+    test_all_i32_variants!(fn test_partition(list) {
+        let (matched, others) = list.partition(|_| true);
+        assert!(matched.is_empty());
+        assert!(others.is_empty());
+
+        others.extend(1..=7);
+        let first = others.front().unwrap() as *const i32;
+        let (matched, others) = others.partition(|v| v % 3 == 1);
+        assert_eq!(matched.len(), 3);
+        assert_eq!(others.len(), 4);
+        // The node is moved, not the value.
+        assert_eq!(matched.front().unwrap() as *const i32, first);
+
+        // Both lists must be able to push to their own backs.
+        matched.push_back(10);
+        others.push_back(11);
+        assert_eq!(matched.into_iter().collect::<Vec<_>>(), vec![1, 4, 7, 10]);
+        assert_eq!(others.into_iter().collect::<Vec<_>>(), vec![2, 3, 5, 6, 11]);
+    });
+
+    test_all_i32_variants!(fn test_split_off(list) {
+        let mut list = list;
+        let rest = list.split_off(0);
+        assert!(list.is_empty());
+        assert!(rest.is_empty());
+
+        list.extend([1, 2, 3, 4, 5]);
+        let rest = list.split_off(5);
+        assert!(rest.is_empty());
+        assert_eq!(list.len(), 5);
+
+        let rest = list.split_off(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(rest.len(), 2);
+        // Both halves must be able to push to their own backs.
+        list.push_back(6);
+        rest.push_back(7);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &6]);
+        assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&4, &5, &7]);
+
+        let rest = list.split_off(0);
+        assert!(list.is_empty());
+        list.push_back(8);
+        assert_eq!(list.len(), 1);
+        assert_eq!(rest.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 6]);
+    });
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_out_of_range() {
+        let mut list = OnceList::<i32>::new();
+        list.extend([1, 2]);
+        let _ = list.split_off(3);
+    }
+
+    test_all_i32_variants!(fn test_append(list) {
+        let mut list = list;
+        let mut other = list.new_empty_like();
+        list.append(&mut other);
+        assert!(list.is_empty());
+
+        other.extend([1, 2]);
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(other.len(), 0);
+        assert_eq!(list.len(), 2);
+
+        // A single node list, and an empty list.
+        other.push_back(3);
+        list.append(&mut other);
+        list.append(&mut other);
+        assert_eq!(list.len(), 3);
+
+        // Both lists must be able to push to their own backs.
+        other.extend([4, 5, 6]);
+        list.append(&mut other);
+        list.push_back(7);
+        other.push_back(8);
+        assert_eq!(list.len(), 7);
+        assert_eq!(other.iter().collect::<Vec<_>>(), vec![&8]);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+    });
+
+    test_all_i32_variants!(fn test_push_front(list) {
+        let mut list = list;
+        list.push_front(3);
+        assert_eq!(list.len(), 1);
+        list.push_front(2);
+        assert_eq!(list.len(), 2);
+        // The tail slot was in the single node, which has been moved behind the new head.
+        list.push_back(4);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(2));
+
+        list.push_back(5);
+        list.push_front(2);
+        list.push_front(1);
+        assert_eq!(list.len(), 5);
+
+        // Pushing to the back must still work.
+        list.push_back(6);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.back(), Some(&6));
+
+        // Usable as a stack.
+        list.push_front(0);
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    });
+
+    test_all_i32_variants!(fn test_pop_back(list) {
+        let mut list = list;
+        assert_eq!(list.pop_back(), None);
+
+        list.extend([1, 2, 3]);
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.len(), 2);
+
+        // Pushing after popping the back must still work.
+        list.push_back(4);
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        list.push_back(5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![5]);
+    });
+
+    test_all_i32_variants!(fn test_insert(list) {
+        let mut list = list;
+        list.insert(0, 2);
+        list.insert(0, 1);
+        list.insert(2, 4);
+        list.insert(2, 3);
+        assert_eq!(list.len(), 4);
+
+        // Pushing after inserting must still work.
+        list.push_back(5);
+        list.insert(5, 6);
+        list.push_back(7);
+        assert_eq!(list.len(), 7);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+    });
+
+    #[test]
+    #[should_panic]
+    fn test_insert_out_of_range() {
+        let mut list = OnceList::<i32>::new();
+        list.extend([1, 2]);
+        list.insert(3, 3);
+    }
+
+    test_all_i32_variants!(fn test_remove_by_ptr(list) {
+        let mut list = list;
+        let ptrs = (0..3)
+            .map(|_| ::std::ptr::from_ref(list.push_back(7)))
+            .collect::<Vec<_>>();
+        assert_eq!(list.remove_by_ptr(ptrs[2]), Some(7));
+        assert_eq!(list.remove_by_ptr(ptrs[0]), Some(7));
+        assert_eq!(list.remove_by_ptr(ptrs[0]), None);
+        assert_eq!(list.len(), 1);
+        assert!(::std::ptr::eq(list.front().unwrap(), ptrs[1]));
+        list.push_back(8);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![7, 8]);
+    });
+
+    test_all_i32_variants!(fn test_remove_at(list) {
+        let mut list = list;
+        assert_eq!(list.remove_at(0), None);
+
+        list.extend([1, 2, 3, 4]);
+        assert_eq!(list.remove_at(4), None);
+        assert_eq!(list.remove_at(0), Some(1));
+        assert_eq!(list.remove_at(2), Some(4));
+        assert_eq!(list.len(), 2);
+
+        // Pushing after removing the back value must still work.
+        list.push_back(5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![2, 3, 5]);
+    });
+
+    test_all_i32_variants!(fn test_get(list) {
+        let mut list = list;
+        assert_eq!(list.get(0), None);
+        assert_eq!(list.get_mut(0), None);
+
+        list.extend([1, 2, 3]);
+        assert_eq!(list.get(0), Some(&1));
+        assert_eq!(list.get(2), Some(&3));
+        assert_eq!(list.get(3), None);
+        *list.get_mut(2).unwrap() = 30;
+        assert_eq!(list.get_mut(3), None);
+
+        list.push_back(4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 30, 4]);
+    });
+
+    test_all_i32_variants!(fn test_get_disjoint_mut(list) {
+        let mut list = list;
+        assert_eq!(list.get_disjoint_mut([0, 1]), [None, None]);
+        assert_eq!(list.get_disjoint_mut([]), [] as [Option<&mut i32>; 0]);
+
+        list.extend([1, 2, 3, 4]);
+        let [a, b, c] = list.get_disjoint_mut([3, 0, 4]);
+        assert_eq!((a.as_deref(), b.as_deref(), c), (Some(&4), Some(&1), None));
+        if let (Some(a), Some(b)) = (a, b) {
+            ::std::mem::swap(a, b);
+        }
+        list.push_back(5);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![4, 2, 3, 1, 5]);
+    });
+
+    #[test]
+    #[should_panic(expected = "the index 1 is duplicated")]
+    fn test_get_disjoint_mut_duplicated() {
+        let mut list = OnceList::from([1, 2]);
+        list.get_disjoint_mut([1, 0, 1]);
+    }
+
+    test_all_i32_variants!(fn test_index(list) {
+        let mut list = list;
+        list.extend([1, 2, 3]);
+        assert_eq!(list[0], 1);
+        assert_eq!(list[2], 3);
+        list[2] = 30;
+        list[0] += 10;
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 2, 30]);
+    });
+
+    #[test]
+    #[should_panic(expected = "index out of range: the len is 2 but the index is 2")]
+    fn test_index_out_of_range() {
+        let list = OnceList::<i32>::new();
+        list.extend([1, 2]);
+        let _ = list[2];
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of range: the len is 2 but the index is 5")]
+    fn test_index_mut_out_of_range() {
+        let mut list = OnceListWithLen::<i32>::new();
+        list.extend([1, 2]);
+        list[5] = 0;
+    }
+
+    test_all_i32_variants!(fn test_reverse(list) {
+        let mut list = list;
+        list.reverse();
+        assert!(list.is_empty());
+
+        list.push_back(1);
+        list.reverse();
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1]);
+
+        list.extend([2, 3, 4]);
+        list.reverse();
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&4, &3, &2, &1]);
+
+        // Pushing after reversing must still work.
+        list.push_back(0);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+    });
+
+    test_all_i32_variants!(fn test_merge(list) {
+        let other = list.new_empty_like();
+        let list = list.merge(other);
+        assert!(list.is_empty());
+
+        list.extend([1, 4, 6]);
+        let other = list.new_empty_like();
+        other.extend([2, 4, 5, 7, 8]);
+        let list = list.merge(other);
+        assert_eq!(list.len(), 8);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            vec![&1, &2, &4, &4, &5, &6, &7, &8]
+        );
+
+        // Stable: the values from the first list come first.
+        let other = list.new_empty_like();
+        other.extend([10, 21]);
+        let list = list.merge_by(other, |a, b| (a / 10).cmp(&(b / 10)));
+        assert_eq!(list.iter().nth(8), Some(&10));
+        assert_eq!(list.back(), Some(&21));
+
+        // Pushing after merging must still work.
+        list.push_back(30);
+        assert_eq!(list.len(), 11);
+        assert_eq!(list.back(), Some(&30));
+    });
+
+    test_all_i32_variants!(fn test_sort(list) {
+        let mut list = list;
+        list.sort();
+        assert!(list.is_empty());
+
+        list.extend([5, 3, 8, 1, 9, 2, 7]);
+        list.sort();
+        assert_eq!(list.len(), 7);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &5, &7, &8, &9]);
+
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&9, &8, &7, &5, &3, &2, &1]);
+
+        // Stable: the values with the same key keep their order.
+        list.sort_by_key(|v| v % 3);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&9, &3, &7, &1, &8, &5, &2]);
+
+        // Pushing after sorting must still work.
+        list.push_back(0);
+        assert_eq!(list.len(), 8);
+        assert_eq!(list.back(), Some(&0));
+    });
+
+    #[test]
+    fn test_sort_keeps_addresses() {
+        let mut list = OnceList::<i32>::new();
+        list.extend((0..100).map(|i| (i * 37) % 100));
+        let mut addrs = list
+            .iter()
+            .map(|v| (*v, v as *const i32))
+            .collect::<Vec<_>>();
+        list.sort();
+        addrs.sort();
+        assert_eq!(
+            list.iter()
+                .map(|v| (*v, v as *const i32))
+                .collect::<Vec<_>>(),
+            addrs
+        );
+    }
+
+    #[test]
+    fn test_sort_by_panic_keeps_values() {
+        use ::std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut list = OnceListWithTailLen::<f64>::new();
+        list.extend([3.0, 5.0, f64::NAN, 1.0, 4.0, 2.0, 7.0, 6.0]);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        }));
+        assert!(result.is_err());
+
+        // Like `slice::sort_by`, all the values stay in the list in some order.
+        assert_eq!(list.len(), 8);
+        assert_eq!(list.iter().count(), 8);
+        assert_eq!(list.iter().filter(|v| v.is_nan()).count(), 1);
+        let mut values = list
+            .iter()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .collect::<Vec<_>>();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(values, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+
+        // The caches are re-primed.
+        list.push_back(8.0);
+        assert_eq!(list.len(), 9);
+        assert_eq!(list.back(), Some(&8.0));
+    }
+
+    test_all_i32_variants!(fn test_dedup(list) {
+        let mut list = list;
+        list.dedup();
+        assert!(list.is_empty());
+
+        list.extend([1, 1, 2, 2, 2, 3, 1, 1]);
+        list.dedup();
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &1]);
+
+        // The later value is passed first, and the earlier one is kept.
+        list.extend([11, 21]);
+        list.dedup_by(|later, earlier| {
+            *earlier += 100;
+            *later % 10 == *earlier % 10
+        });
+        assert_eq!(list.len(), 4);
+        list.push_back(4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![101, 102, 103, 201, 4]);
+    });
+
+    test_all_i32_variants!(fn test_truncate(list) {
+        let mut list = list;
+        list.truncate(0);
+        assert!(list.is_empty());
+
+        list.extend([1, 2, 3, 4, 5]);
+        list.truncate(5);
+        list.truncate(10);
+        assert_eq!(list.len(), 5);
+
+        list.truncate(3);
+        assert_eq!(list.len(), 3);
+        // Pushing must not write past the truncation point.
+        list.push_back(6);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &6]);
+
+        list.truncate(0);
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        list.push_back(7);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![7]);
+    });
+
+    test_all_i32_variants!(fn test_replace(list) {
+        let mut list = list;
+        assert_eq!(list.replace(|_| true, 0), None);
+        assert!(list.is_empty());
+
+        list.extend([1, 2, 3, 2]);
+        let addr = list.get(1).unwrap() as *const i32;
+        assert_eq!(list.replace(|&v| v == 2, 20), Some(2));
+        assert_eq!(list.get(1).unwrap() as *const i32, addr);
+        assert_eq!(list.replace(|&v| v == 5, 50), None);
+        assert_eq!(list.len(), 4);
+
+        list.push_back(4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 20, 3, 2, 4]);
+    });
+
+    test_all_i32_variants!(fn test_position(list) {
+        assert_eq!(list.position(|_| true), None);
+        assert_eq!(list.rposition(|_| true), None);
+
+        list.extend([1, 2, 3, 1, 2]);
+        assert_eq!(list.position(|&v| v == 2), Some(1));
+        assert_eq!(list.rposition(|&v| v == 2), Some(4));
+        assert_eq!(list.position(|&v| v == 3), Some(2));
+        assert_eq!(list.rposition(|&v| v == 3), Some(2));
+        assert_eq!(list.position(|&v| v > 3), None);
+        assert_eq!(list.rposition(|&v| v > 3), None);
+    });
+
+    test_all_i32_variants!(fn test_remove_all(list) {
+        let mut list = list;
+        assert!(list.remove_all(|_| true).is_empty());
+
+        list.extend([1, 2, 3, 4, 5, 6]);
+        assert_eq!(list.remove_all(|v| v % 3 == 0), vec![3, 6]);
+        assert_eq!(list.len(), 4);
+        assert!(list.remove_all(|&v| v > 10).is_empty());
+
+        list.push_back(7);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 4, 5, 7]);
+    });
+
+    test_all_i32_variants!(fn test_to_vec(list) {
+        assert_eq!(list.to_vec(), Vec::<i32>::new());
+        list.extend([1, 2, 3]);
+        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.into_vec(), vec![1, 2, 3]);
+    });
+
+    #[test]
+    fn test_from_fn_and_repeat() {
+        let list = OnceListWithTailLen::<usize>::from_fn(3, |i| i * 10);
+        assert_eq!(list.len(), 3);
+        list.push_back(30);
+        assert_eq!(list, [0, 10, 20, 30]);
+        assert!(OnceList::<usize>::from_fn(0, |i| i).is_empty());
+
+        let list = OnceList::repeat("a".to_string(), 2);
+        assert_eq!(list, ["a", "a"]);
+        assert!(OnceList::repeat(1, 0).is_empty());
+
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithLen::<i32, _>::repeat_in(7, 4, alloc.clone());
+        assert_eq!(list.len(), 4);
+        assert_eq!(alloc.live.get(), 4);
+        let list = OnceList::<i32, _>::from_fn_in(2, |i| i as i32, alloc.clone());
+        assert_eq!(list, [0, 1]);
+        assert_eq!(alloc.live.get(), 6);
+    }
+
+    #[test]
+    fn test_try_from_iter() {
+        let list = OnceListWithLen::<i32>::try_from_iter([Ok::<_, ()>(1), Ok(2)]);
+        assert_eq!(list.map(|l| l.len()), Ok(2));
+
+        let mut rest = vec![Ok(1), Err("a"), Ok(3), Err("b")].into_iter();
+        let Err((err, partial)) = OnceListWithTailLen::<i32>::try_from_iter(&mut rest) else {
+            panic!("try_from_iter: expected an error");
+        };
+        assert_eq!(err, "a");
+        assert_eq!(partial.len(), 1);
+        partial.push_back(2);
+        assert_eq!(partial, [1, 2]);
+        // The rest of the iterator is not consumed.
+        assert_eq!(rest.next(), Some(Ok(3)));
+    }
+
+    test_all_i32_variants!(fn test_try_extend_results(list) {
+        assert_eq!(list.try_extend_results([Ok(1), Ok(2)]), Ok::<_, &str>(()));
+        assert_eq!(list.try_extend_results([Ok(3), Err("a"), Ok(4)]), Err("a"));
+        assert_eq!(list.len(), 3);
+        list.push_back(5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 5]);
+    });
+
+    #[test]
+    fn test_list_from_collections() {
+        let list = OnceList::from(vec![1, 2, 3]);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+        let list = OnceList::from(["a".to_string(), "b".to_string()]);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec!["a", "b"]);
+        let list = OnceList::from(&[4, 5][..]);
+        list.push(6);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&4, &5, &6]);
+        let list = OnceList::<i32>::from([]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_vec_from_list() {
+        let list = OnceListWithLen::<String>::new();
+        list.extend(["a".to_string(), "b".to_string()]);
+        let vec = Vec::from(list);
+        assert_eq!(vec, vec!["a", "b"]);
+        assert!(vec.capacity() >= 2);
+
+        let vec: Vec<i32> = OnceList::<i32>::new().into();
+        assert!(vec.is_empty());
+    }
+
+    test_all_i32_variants!(fn test_move_to_front(list) {
+        let mut list = list;
+        assert!(!list.move_to_front(|_| true));
+
+        list.extend([1, 2, 3, 4]);
+        assert!(list.move_to_front(|&v| v == 3));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3, &1, &2, &4]);
+        assert!(!list.move_to_front(|&v| v == 3));
+        assert!(!list.move_to_front(|&v| v == 5));
+
+        // Moving the back value must not break the tail insertion.
+        assert!(list.move_to_front(|&v| v == 4));
+        list.push_back(5);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![4, 3, 1, 2, 5]);
+    });
+
+    test_all_i32_variants!(fn test_cursor(list) {
+        let mut cursor = list.cursor_front();
+        assert_eq!((cursor.index(), cursor.current(), cursor.peek_next()), (0, None, None));
+        cursor.move_next();
+        assert_eq!(cursor.index(), 0);
+
+        list.extend([1, 2, 3]);
+        assert_eq!((cursor.current(), cursor.peek_next()), (Some(&1), Some(&2)));
+        let paused = cursor;
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!((cursor.index(), cursor.current(), cursor.peek_next()), (2, Some(&3), None));
+        cursor.move_next();
+        assert_eq!((cursor.index(), cursor.current()), (3, None));
+
+        // A copied cursor resumes from its own position.
+        assert_eq!((paused.index(), paused.current()), (0, Some(&1)));
+        list.push_back(4);
+        assert_eq!(cursor.current(), Some(&4));
+    });
+
+    test_all_i32_variants!(fn test_cursor_mut(list) {
+        let mut list = list;
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(cursor.current(), None);
+        // Inserting at the end appends the value, which becomes the current one.
+        cursor.insert_after(2);
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.insert_after(4);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!((cursor.index(), cursor.as_cursor().current()), (2, None));
+        cursor.insert_after(5);
+        assert_eq!(list.len(), 3);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_after(3);
+        *cursor.current().unwrap() = 1;
+        cursor.move_next();
+        *cursor.peek_next().unwrap() += 1;
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!((cursor.index(), cursor.current()), (1, Some(&mut 5)));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(5));
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(list.len(), 2);
+
+        list.push_back(6);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 5, 6]);
+    });
+
+    test_all_i32_variants!(fn test_cursor_mut_splice_after(list) {
+        let mut list = list;
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_after(OnceList::new());
+        // Splicing at the end appends the values, and the first one becomes the current one.
+        cursor.splice_after(OnceList::from([1, 5]));
+        assert_eq!(cursor.current(), Some(&mut 1));
+
+        let other = OnceListWithTail::<i32>::new();
+        other.extend([2, 3]);
+        other.push_back(4);
+        cursor.splice_after(other);
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(list.len(), 5);
+
+        list.push_back(6);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    });
+
+    #[test]
+    fn test_cursor_mut_splice_after_single_node() {
+        // The other list's cached tail slot lives in its only node, which is moved by the splice.
+        let mut list = OnceListWithTailLen::<i32>::new();
+        list.extend([1, 3]);
+        let other = OnceListWithTail::<i32>::new();
+        other.push_back(2);
+        list.cursor_front_mut().splice_after(other);
+        assert_eq!(list.len(), 3);
+
+        list.push_back(4);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    }
+
+    test_all_i32_variants!(fn test_cursor_back(list) {
+        let mut list = list;
+        assert_eq!((list.cursor_back().index(), list.cursor_back().current()), (0, None));
+        assert_eq!(list.cursor_back_mut().current(), None);
+
+        list.extend([1, 2, 3]);
+        let cursor = list.cursor_back();
+        assert_eq!((cursor.index(), cursor.current(), cursor.peek_next()), (2, Some(&3), None));
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.index(), 2);
+        cursor.insert_after(5);
+        cursor.insert_after(4);
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(list.len(), 4);
+        list.push_back(6);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 4, 5, 6]);
+    });
+
+    test_all_i32_variants!(fn test_cursor_mut_split_after(list) {
+        let mut list = list;
+        assert!(list.cursor_front_mut().split_after().is_empty());
+
+        list.extend([1, 2, 3, 4]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let rest = cursor.split_after();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.peek_next(), None);
+        cursor.move_next();
+        assert!(cursor.split_after().is_empty());
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(rest.len(), 2);
+        list.push_back(5);
+        rest.push_back(6);
+        assert_eq!(list.len(), 3);
+        assert_eq!(rest.len(), 3);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 5]);
+        assert_eq!(rest.into_iter().collect::<Vec<_>>(), vec![3, 4, 6]);
+    });
+
+    test_all_i32_variants!(fn test_iter_sees_push_after_exhausted(list) {
+        list.push_back(1);
+
+        let mut it = list.iter();
+        assert_eq!(it.next(), Some(&1));
+        assert_eq!(it.next(), None);
+
+        // After the iterator reached the end, pushing a new element should make it visible
+        // from the same iterator.
+        list.push_back(2);
+        assert_eq!(it.next(), Some(&2));
+        assert_eq!(it.next(), None);
+    });
+
+    test_all_i32_variants!(fn test_iter_sees_extend_after_exhausted(list) {
+        list.push_back(1);
+
+        let mut it = list.iter();
+        assert_eq!(it.next(), Some(&1));
+        assert_eq!(it.next(), None);
+
+        // Same property should hold for `extend()` as well.
+        list.extend([2, 3]);
+        assert_eq!(it.next(), Some(&2));
+        assert_eq!(it.next(), Some(&3));
+        assert_eq!(it.next(), None);
+    });
+
+    test_all_i32_variants!(fn test_iter_mut_allows_in_place_update(list) {
+        let mut list = list;
+        list.extend([1, 2, 3]);
+        for v in list.iter_mut() {
+            *v += 10;
+        }
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 12, 13]);
+    });
+
+    test_all_i32_variants!(fn test_push_after_mut_access(list) {
+        // Mutable access to the nodes must not leave a stale tail slot behind. Under Miri with
+        // `--features nightly` this checks the pointer is not reused after the node is
+        // reborrowed.
+        let mut list = list;
+        list.extend([1, 2, 3]);
+        *list.back_mut().unwrap() += 10;
+        list.push_back(4);
+        for v in list.iter_mut() {
+            *v += 1;
+        }
+        list.push_back(5);
+        *list.last_mut().unwrap() += 10;
+        list.push_back(6);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![2, 3, 14, 5, 15, 6]);
+    });
+
+    test_all_i32_variants!(fn test_iter_mut_rev(list) {
+        let mut list = list;
+        assert!(list.iter_mut_rev().next().is_none());
+
+        list.extend([1, 2, 3]);
+        let mut visited = Vec::new();
+        for v in list.iter_mut_rev() {
+            visited.push(*v);
+            *v -= 1;
+        }
+        assert_eq!(visited, vec![3, 2, 1]);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+    });
+
+    test_all_i32_variants!(fn test_swap(list) {
+        let mut list = list;
+        list.extend([1, 2, 3, 4]);
+        let addrs = list.iter().map(|v| v as *const i32).collect::<Vec<_>>();
+        list.swap(0, 3);
+        list.swap(2, 1);
+        list.swap(2, 2);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&4, &3, &2, &1]);
+        // The nodes are not relinked.
+        assert_eq!(list.iter().map(|v| v as *const i32).collect::<Vec<_>>(), addrs);
+
+        // Swapping borrows the nodes mutably, which drops the cached tail slot. Pushing after it
+        // finds the tail again, and the length cache is kept.
+        list.push_back(5);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.back(), Some(&5));
+    });
+
+    #[test]
+    #[should_panic(expected = "index out of range: the len is 2 but the index is 2")]
+    fn test_swap_out_of_range() {
+        let mut list = OnceList::from([1, 2]);
+        list.swap(2, 0);
+    }
+
+    test_all_i32_variants!(fn test_fill(list) {
+        let mut list = list;
+        list.fill(0);
+        assert!(list.is_empty());
+
+        list.extend([1, 2, 3]);
+        list.fill(7);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&7, &7, &7]);
+
+        let mut next = 0;
+        list.fill_with(|| {
+            next += 1;
+            next * 10
+        });
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![10, 20, 30]);
+    });
+
+    test_all_i32_variants!(fn test_into_iter_for_ref(list) {
+        list.extend([1, 2, 3]);
+
+        // `IntoIterator for &OnceListCore` should yield `&T`.
+        let collected = (&list).into_iter().copied().collect::<Vec<_>>();
+        assert_eq!(collected, vec![1, 2, 3]);
+
+        // Also ensure `for x in &list` works (uses the same `IntoIterator` impl).
+        let mut sum = 0;
+        for &v in &list {
+            sum += v;
+        }
+        assert_eq!(sum, 6);
+    });
+
+    test_all_i32_variants!(fn test_into_iter_for_mut_ref_allows_in_place_update(list) {
+        let mut list = list;
+        list.extend([1, 2, 3]);
+
+        // `IntoIterator for &mut OnceListCore` should yield `&mut T`.
+        for v in &mut list {
+            *v += 10;
+        }
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 12, 13]);
+    });
+
+    test_all_i32_variants!(fn test_iter_mut_empty_and_singleton(list) {
+        // Empty list
+        {
+            let mut empty = list;
+            let mut it = empty.iter_mut();
+            assert!(it.next().is_none());
+
+            // Singleton list (reuse the same list type/instance)
+            empty.push_back(1);
+            let mut it = empty.iter_mut();
+            let v = it.next().unwrap();
+            *v = 2;
+            assert!(it.next().is_none());
+            assert_eq!(empty.into_iter().collect::<Vec<_>>(), vec![2]);
+        }
+    });
+
+    #[test]
+    fn test_leak() {
+        fn registry() -> &'static mut OnceListWithTailLen<String> {
+            let list = OnceListWithTailLen::<String>::new();
+            list.push_back("a".to_string());
+            list.leak()
+        }
+
+        let raw: *mut OnceListWithTailLen<String> = registry();
+        let list: &'static OnceListWithTailLen<String> = unsafe { &*raw };
+        let b: &'static str = list.push_back("b".to_string());
+        let all: Vec<&'static String> = list.iter().collect();
+        assert_eq!(all, vec!["a", "b"]);
+        assert_eq!(b, "b");
+        assert_eq!(list.len(), 2);
+
+        // The leaked list is still mutable through the returned reference.
+        let list = OnceList::from([1, 2]).leak();
+        list.retain(|&v| v == 2);
+        assert_eq!(*list, [2]);
+
+        // Free the leaked lists, so that the test passes Miri's leak check.
+        drop(unsafe { ::std::boxed::Box::from_raw(raw) });
+        drop(unsafe { ::std::boxed::Box::from_raw(list) });
+    }
+
+    test_all_i32_variants!(fn test_eq(list1) {
+        list1.extend([1, 2, 3]);
+
+        let list2 = {
+            let l = OnceList::<i32>::new();
+            l.extend([1, 2, 3]);
+            l
+        };
+        assert_eq!(
+            list1.iter().collect::<Vec<_>>(),
+            list2.iter().collect::<Vec<_>>()
+        );
+
+        let list3 = {
+            let l = OnceList::<i32>::new();
+            l.extend([1, 2, 4]);
+            l
+        };
+        assert_ne!(
+            list1.iter().collect::<Vec<_>>(),
+            list3.iter().collect::<Vec<_>>()
+        );
+    });
+
+    test_all_i32_variants!(fn test_eq_ignore_order(list) {
+        let other = OnceListWithTail::<i32>::new();
+        assert!(list.eq_ignore_order(&other));
+        assert!(list.eq_ignore_order_hashed(&other));
+
+        list.extend([1, 2, 2, 3]);
+        other.extend([3, 2, 1, 2]);
+        assert!(list.eq_ignore_order(&other));
+        assert!(list.eq_ignore_order_hashed(&other));
+
+        other.push_back(2);
+        assert!(!list.eq_ignore_order(&other));
+        assert!(!list.eq_ignore_order_hashed(&other));
+
+        let other = OnceList::from([1, 2, 3, 3]);
+        assert!(!list.eq_ignore_order(&other));
+        assert!(!list.eq_ignore_order_hashed(&other));
+    });
+
+    test_all_i32_variants!(fn test_set_operations(list) {
+        let other = OnceListWithLen::<i32>::new();
+        assert_eq!(list.union(&other).next(), None);
+        assert_eq!(list.union_hashed(&other).next(), None);
+
+        list.extend([1, 2, 3, 4]);
+        other.extend([5, 4, 2, 6]);
+        let union = vec![&1, &2, &3, &4, &5, &6];
+        assert_eq!(list.union(&other).collect::<Vec<_>>(), union);
+        assert_eq!(list.union_hashed(&other).collect::<Vec<_>>(), union);
+        assert_eq!(list.intersection(&other).collect::<Vec<_>>(), vec![&2, &4]);
+        assert_eq!(list.intersection_hashed(&other).collect::<Vec<_>>(), vec![&2, &4]);
+        assert_eq!(list.difference(&other).collect::<Vec<_>>(), vec![&1, &3]);
+        assert_eq!(list.difference_hashed(&other).collect::<Vec<_>>(), vec![&1, &3]);
+        assert_eq!(other.difference(&list).collect::<Vec<_>>(), vec![&5, &6]);
+    });
+
+    test_all_i32_variants!(fn test_hash(list1) {
+        use ::std::hash::{DefaultHasher, Hasher};
+        list1.extend([1, 2, 3]);
+
+        let list2 = {
+            let l = OnceList::<i32>::new();
+            l.extend([1, 2, 3]);
+            l
+        };
+
+        let mut hasher1 = DefaultHasher::new();
+        let mut hasher2 = DefaultHasher::new();
+        list1.hash(&mut hasher1);
+        list2.hash(&mut hasher2);
+        assert_eq!(hasher1.finish(), hasher2.finish());
+    });
+
+    #[test]
+    fn test_push_boxed() {
+        use ::allocator_api2::boxed::Box;
+        use ::std::cell::Cell;
+        use ::std::fmt::Debug;
+        use ::std::rc::Rc;
+
+        fn boxed_debug<T: Debug + 'static>(val: T) -> Box<dyn Debug> {
+            let (ptr, alloc) = Box::into_raw_with_allocator(Box::new(val));
+            // Pointer unsized coercion.
+            let ptr: *mut dyn Debug = ptr;
+            unsafe { Box::from_raw_in(ptr, alloc) }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        let list = OnceList::<dyn Debug>::new();
+        list.push_boxed(boxed_debug(1u8));
+        list.push_boxed(boxed_debug(DropFlag(0, drops.clone())));
+        list.push_boxed(boxed_debug(()));
+        list.push_boxed(boxed_debug([1u64, 2, 3]));
+        assert_eq!(format!("{:?}", list), "[1, DropFlag, (), [1, 2, 3]]");
+        assert_eq!(drops.get(), 0);
+        drop(list);
+        assert_eq!(drops.get(), 1);
+
+        let list = OnceListWithTailLen::<[u64]>::new();
+        let first = list.push_boxed(Box::from(&[1u64, 2][..]));
+        list.push_boxed(Box::from(&[][..]));
+        list.push_boxed(Box::from(&[3u64, 4, 5][..]));
+        assert_eq!(first, &[1, 2]);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            vec![&[1u64, 2][..], &[], &[3, 4, 5]]
+        );
+        assert_eq!(list.len(), 3);
+
+        let list = OnceList::<i32>::new();
+        assert_eq!(list.push_boxed(Box::new(42)), &42);
+    }
+
+    #[test]
+    fn test_pop_front_boxed() {
+        use ::allocator_api2::boxed::Box;
+        use ::std::any::Any;
+        use ::std::cell::Cell;
+        use ::std::rc::Rc;
+
+        let drops = Rc::new(Cell::new(0));
+        let mut list = OnceListWithTailLen::<dyn Any>::new();
+        list.push_any(DropFlag(0, drops.clone()));
+        list.push_any(());
+        list.push_any(DropFlag(0, drops.clone()));
+
+        let first = list.pop_front_boxed().unwrap();
+        assert!(first.is::<DropFlag>());
+        assert_eq!(list.len(), 2);
+        assert_eq!(drops.get(), 0);
+        drop(first);
+        assert_eq!(drops.get(), 1);
+
+        let second = list.pop_front_boxed().unwrap();
+        assert!(second.is::<()>());
+        drop(list);
+        assert_eq!(drops.get(), 2);
+
+        let mut list = OnceList::<[u64]>::new();
+        list.push_slice(&[1, 2]);
+        list.push_slice(&[]);
+        assert_eq!(list.pop_front_boxed(), Some(Box::from(&[1u64, 2][..])));
+        assert_eq!(list.pop_front_boxed(), Some(Box::from(&[][..])));
+        assert_eq!(list.pop_front_boxed(), None);
+
+        // The popped slice drops each element exactly once, and the list drops the rest.
+        let drops = Rc::new(Cell::new(0));
+        let mut list = OnceList::<[DropFlag]>::new();
+        list.push_boxed(Box::from_iter([
+            DropFlag(0, drops.clone()),
+            DropFlag(0, drops.clone()),
+        ]));
+        list.push_boxed(Box::from_iter([DropFlag(0, drops.clone())]));
+        let popped = list.pop_front_boxed().unwrap();
+        assert_eq!(popped.len(), 2);
+        assert_eq!(drops.get(), 0);
+        drop(popped);
+        assert_eq!(drops.get(), 2);
+        drop(list);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_slice_push_and_extend() {
+        let list = OnceListWithTailLen::<[String]>::new();
+        let first = list.push_slice(&["a".to_string()]);
+        assert_eq!(first, &["a".to_string()]);
+
+        list.extend_slices((0..300).map(|i| vec![i.to_string(); i % 3]));
+        list.extend_slices([&[][..]]);
+        assert_eq!(list.len(), 302);
+        assert!(list
+            .iter()
+            .skip(1)
+            .take(300)
+            .zip(0..)
+            .all(|(s, i)| s == vec![i.to_string(); i % 3].as_slice()));
+        assert_eq!(list.back(), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_slice_clone() {
+        let list = OnceListWithLen::<[String]>::new();
+        list.push_slice(&["a".to_string()]);
+        list.push_slice(&[]);
+        list.push_slice(&["b".to_string(), "c".to_string()]);
+
+        let mut cloned = list.clone();
+        assert_eq!(cloned, list);
+        assert_eq!(cloned.len(), 3);
+
+        for s in cloned.iter_mut().flatten() {
+            s.push('!');
+        }
+        assert_eq!(
+            cloned.iter().flatten().collect::<Vec<_>>(),
+            vec!["a!", "b!", "c!"]
+        );
+        assert_eq!(
+            list.iter().flatten().collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_slice_push_clone_panic() {
+        use ::std::cell::Cell;
+        use ::std::panic::{catch_unwind, AssertUnwindSafe};
+        use ::std::rc::Rc;
+
+        let countdown = Rc::new(Cell::new(2));
+        let drops = Rc::new(Cell::new(0));
+        let src = (0..3)
+            .map(|_| Bomb {
+                countdown: countdown.clone(),
+                drops: drops.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let list = OnceList::<[Bomb]>::new();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.push_slice(&src);
+        }));
+        assert!(result.is_err());
+        assert!(list.is_empty());
+        // The two successfully cloned elements are dropped.
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn test_slice_remove_array() {
+        use ::allocator_api2::boxed::Box;
+
+        let mut list = OnceListWithTailLen::<[String]>::new();
+        for s in [&["a"][..], &["b", "c"], &["d", "e"], &[]] {
+            list.push_boxed(Box::from_iter(s.iter().map(|s| s.to_string())));
+        }
+
+        // No element has length 3.
+        assert_eq!(list.remove_array::<3>(), None);
+        assert_eq!(list.len(), 4);
+
+        // The first one wins if several elements have the same length.
+        assert_eq!(
+            list.remove_array::<2>(),
+            Some(["b".to_string(), "c".to_string()])
+        );
+        assert_eq!(list.remove_array::<0>(), Some([]));
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            vec![&["a".to_string()][..], &["d".to_string(), "e".to_string()]]
+        );
+
+        // The list still accepts pushes after the removals.
+        list.push_boxed(Box::from_iter(["f".to_string()]));
+        assert_eq!(list.remove_array::<1>(), Some(["a".to_string()]));
+        assert_eq!(list.remove_array::<1>(), Some(["f".to_string()]));
+        assert!(list.remove_array::<1>().is_none());
+    }
+
+    #[test]
+    fn test_slice_remove_slice_where() {
+        use ::allocator_api2::boxed::Box;
+
+        let mut list = OnceList::<[String]>::new();
+        for s in [&["a"][..], &["b", "c"], &[], &["d", "e"]] {
+            list.push_boxed(Box::from_iter(s.iter().map(|s| s.to_string())));
+        }
+
+        assert_eq!(list.remove_slice_where(|s| s.len() == 3), None);
+        let removed = list.remove_slice_where(|s| s.len() == 2);
+        assert_eq!(
+            removed.as_deref(),
+            Some(&["b".to_string(), "c".to_string()][..])
+        );
+        let removed = list.remove_slice_where(|s| s.is_empty());
+        assert_eq!(removed.as_deref(), Some(&[][..]));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_drop_long_list() {
+        const LEN: usize = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let list = OnceListWithTail::<u8>::new();
+        list.extend((0..LEN).map(|i| i as u8));
+        drop(list);
+
+        let mut list = OnceListWithTailLen::<u8>::new();
+        list.extend((0..LEN).map(|i| i as u8));
+        list.clear();
+        assert!(list.is_empty());
+
+        let list = OnceListWithTail::<u8>::new();
+        list.extend((0..LEN).map(|i| i as u8));
+        let mut iter = list.into_iter();
+        assert_eq!(iter.by_ref().take(LEN / 2).count(), LEN / 2);
+        drop(iter);
+
+        let list = OnceList::<str>::new();
+        list.extend_strs((0..LEN).map(|_| "a"));
+        drop(list);
+    }
+
+    #[test]
+    fn test_clone_long_list() {
+        const LEN: usize = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let list = OnceListWithTailLen::<u8>::new();
+        list.extend((0..LEN).map(|i| i as u8));
+        let cloned = list.clone();
+        assert_eq!(cloned.len(), LEN);
+        assert!(cloned.iter().eq(list.iter()));
+
+        // The clone's tail cache is primed.
+        cloned.push_back(42);
+        assert_eq!(cloned.back(), Some(&42));
+        assert_eq!(list.len(), LEN);
+    }
+
+    #[test]
+    fn test_clone_panic() {
+        use ::std::cell::Cell;
+        use ::std::panic::{catch_unwind, AssertUnwindSafe};
+        use ::std::rc::Rc;
+
+        let countdown = Rc::new(Cell::new(2));
+        let drops = Rc::new(Cell::new(0));
+        let list = OnceListWithTail::<Bomb>::new();
+        list.extend((0..3).map(|_| Bomb {
+            countdown: countdown.clone(),
+            drops: drops.clone(),
+        }));
+
+        let result = catch_unwind(AssertUnwindSafe(|| list.clone()));
+        assert!(result.is_err());
+        // The two successfully cloned values are dropped, and the source is intact.
+        assert_eq!(drops.get(), 2);
+        assert_eq!(list.len(), 3);
+        drop(list);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    #[cfg(not(feature = "sync"))]
+    fn test_extend_per_value() {
+        use ::std::panic::{catch_unwind, AssertUnwindSafe};
+
+        // Each value is visible as soon as the iterator yields it.
+        let list = OnceListWithLen::<usize>::new();
+        list.extend((0..3).inspect(|&i| assert_eq!(list.len(), i)));
+        assert_eq!(list.len(), 3);
+
+        // The values yielded before a panic stay in the list.
+        let list = OnceListWithTailLen::<i32>::new();
+        list.push_back(0);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.extend((1..5).inspect(|&i| assert!(i != 3, "boom")));
+        }));
+        assert!(result.is_err());
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&0, &1, &2]);
+        list.push_back(5);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.back(), Some(&5));
+    }
+
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_extend_contention() {
+        const THREADS: usize = 4;
+        const BATCHES: usize = if cfg!(miri) { 4 } else { 200 };
+        const BATCH_LEN: usize = 8;
+
+        let list = OnceList::<(usize, usize, usize)>::new();
+        ::std::thread::scope(|s| {
+            for t in 0..THREADS {
+                let list = &list;
+                s.spawn(move || {
+                    for b in 0..BATCHES {
+                        list.extend((0..BATCH_LEN).map(|i| (t, b, i)));
+                    }
+                });
+            }
+        });
+
+        assert_eq!(list.len(), THREADS * BATCHES * BATCH_LEN);
+        // Each batch is kept contiguous, even when other threads extend concurrently.
+        let values = list.iter().collect::<Vec<_>>();
+        for chunk in values.chunks(BATCH_LEN) {
+            let (t, b, _) = *chunk[0];
+            assert!(chunk.iter().zip(0..).all(|(&&v, i)| v == (t, b, i)));
+        }
+    }
+
+    /// A value which counts its drops in the shared counter.
+    #[derive(Clone)]
+    struct DropFlag(i32, ::std::rc::Rc<::std::cell::Cell<usize>>);
+
+    impl ::std::fmt::Debug for DropFlag {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            f.write_str("DropFlag")
+        }
+    }
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    /// A value which panics when the shared clone countdown reaches zero, and counts its drops.
+    struct Bomb {
+        countdown: ::std::rc::Rc<::std::cell::Cell<usize>>,
+        drops: ::std::rc::Rc<::std::cell::Cell<usize>>,
+    }
+
+    impl Clone for Bomb {
+        fn clone(&self) -> Self {
+            let n = self.countdown.get();
+            assert!(n != 0, "boom");
+            self.countdown.set(n - 1);
+            Bomb {
+                countdown: self.countdown.clone(),
+                drops: self.drops.clone(),
+            }
+        }
+    }
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    /// An allocator which fails after the given number of allocations, and counts the live
+    /// allocations.
+    #[derive(Clone)]
+    struct FailingAlloc {
+        remaining: ::std::rc::Rc<::std::cell::Cell<usize>>,
+        live: ::std::rc::Rc<::std::cell::Cell<usize>>,
+    }
+
+    impl FailingAlloc {
+        fn new(remaining: usize) -> Self {
+            Self {
+                remaining: ::std::rc::Rc::new(::std::cell::Cell::new(remaining)),
+                live: ::std::rc::Rc::new(::std::cell::Cell::new(0)),
+            }
+        }
+    }
+
+    unsafe impl ::allocator_api2::alloc::Allocator for FailingAlloc {
+        fn allocate(
+            &self,
+            layout: ::allocator_api2::alloc::Layout,
+        ) -> Result<::std::ptr::NonNull<[u8]>, ::allocator_api2::alloc::AllocError> {
+            let remaining = self
+                .remaining
+                .get()
+                .checked_sub(1)
+                .ok_or(::allocator_api2::alloc::AllocError)?;
+            self.remaining.set(remaining);
+            self.live.set(self.live.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(
+            &self,
+            ptr: ::std::ptr::NonNull<u8>,
+            layout: ::allocator_api2::alloc::Layout,
+        ) {
+            self.live.set(self.live.get() - 1);
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    #[test]
+    fn test_try_clone() {
+        use ::std::cell::Cell;
+        use ::std::rc::Rc;
+
+        let drops = Rc::new(Cell::new(0));
+
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithTailLen::<DropFlag, _>::new_in(alloc.clone());
+        list.extend((0..5).map(|i| DropFlag(i, drops.clone())));
+        assert_eq!(alloc.live.get(), 5);
+
+        // Fails at the 4th allocation.
+        alloc.remaining.set(3);
+        assert!(list.try_clone().is_err());
+        assert_eq!(alloc.live.get(), 5);
+        // The 3 cloned nodes, and the 4th value whose node allocation failed.
+        assert_eq!(drops.get(), 4);
+
+        alloc.remaining.set(5);
+        let cloned = list.try_clone().ok().unwrap();
+        assert_eq!(alloc.live.get(), 10);
+        assert_eq!(cloned.len(), 5);
+        assert!(cloned.iter().map(|v| v.0).eq(0..5));
+        alloc.remaining.set(usize::MAX);
+        cloned.push_back(DropFlag(5, drops.clone()));
+        assert_eq!(cloned.back().map(|v| v.0), Some(5));
+
+        // A clone into another allocator.
+        let other = FailingAlloc::new(0);
+        assert!(list.try_clone_in(other.clone()).is_err());
+        other.remaining.set(5);
+        let cloned_in = list.try_clone_in(other.clone()).ok().unwrap();
+        assert_eq!(other.live.get(), 5);
+        drop(cloned_in);
+        assert_eq!(other.live.get(), 0);
+    }
+
+    #[test]
+    fn test_try_push_and_try_extend() {
+        let alloc = FailingAlloc::new(2);
+        let list = OnceListWithTailLen::<i32, _>::new_in(alloc.clone());
+        assert_eq!(list.try_push(1), Ok(&1));
+        assert_eq!(list.try_push(2), Ok(&2));
+        assert!(list.try_push(3).is_err());
+        assert_eq!(list.len(), 2);
+
+        // Fails at the 3rd allocation, keeping the first 2 values.
+        alloc.remaining.set(2);
+        assert_eq!(
+            list.try_extend([3, 4, 5, 6]),
+            Err(TryExtendError { inserted: 2 })
+        );
+        assert_eq!(list.len(), 4);
+        assert_eq!(alloc.live.get(), 4);
+
+        alloc.remaining.set(usize::MAX);
+        assert_eq!(list.try_extend([5, 6]), Ok(()));
+        list.push_back(7);
+        assert_eq!(list.len(), 7);
+        assert!(list.iter().copied().eq(1..=7));
+    }
+
+    #[test]
+    fn test_raw_nodes() {
+        use crate::raw::NodeId;
+        use ::std::collections::HashMap;
+
+        let mut list = OnceListWithTailLen::<i32>::new();
+        assert!(list.front_node().is_none());
+
+        let ids = (0..5)
+            .map(|i| (i, list.push_back_node(i * 10).id()))
+            .collect::<HashMap<_, NodeId<_, _>>>();
+        assert_eq!(
+            ids.values()
+                .collect::<::std::collections::HashSet<_>>()
+                .len(),
+            5
+        );
+
+        // Navigation.
+        let front = list.front_node().unwrap();
+        assert_eq!(front.value(), &0);
+        assert_eq!(front.id(), ids[&0]);
+        let mut node = Some(front);
+        let mut values = Vec::new();
+        while let Some(n) = node {
+            values.push(*n.value());
+            node = n.next();
+        }
+        assert_eq!(values, vec![0, 10, 20, 30, 40]);
+
+        let second = front.next().unwrap();
+        assert!(second.iter_from().copied().eq([10, 20, 30, 40]));
+
+        // Lookups by id.
+        assert_eq!(list.get_by_id(ids[&3]), Some(&30));
+        *list.get_mut_by_id(ids[&3]).unwrap() += 5;
+        assert_eq!(list.get_by_id(ids[&3]), Some(&35));
+        *list.get_mut_by_id(ids[&3]).unwrap() -= 5;
+
+        // Removing the middle, the head and the tail.
+        assert_eq!(list.remove_node(ids[&2]), Some(20));
+        assert_eq!(list.remove_node(ids[&0]), Some(0));
+        assert_eq!(list.remove_node(ids[&4]), Some(40));
+        // The ids of the other nodes are still valid after the removals.
+        assert_eq!(list.front_node().unwrap().id(), ids[&1]);
+        assert_eq!(list.remove_node(ids[&3]), Some(30));
+        assert_eq!(list.get_by_id(ids[&3]), None);
+        assert_eq!(list.get_mut_by_id(ids[&3]), None);
+        assert_eq!(list.len(), 1);
+        list.push_back(50);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&10, &50]);
+
+        let mut list = OnceList::<str>::new();
+        list.push_str("a");
+        let id = list.front_node().unwrap().id();
+        list.push_str("b");
+        assert_eq!(list.remove_node_into_box(id).as_deref(), Some("a"));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    /// An allocator which hands the last freed block back to the next allocation with the same
+    /// layout, to reuse an address deterministically.
+    #[derive(Clone, Default)]
+    struct ReusingAlloc {
+        freed: ::std::rc::Rc<::std::cell::Cell<Option<FreedBlock>>>,
+    }
+
+    type FreedBlock = (::std::ptr::NonNull<u8>, ::allocator_api2::alloc::Layout);
+
+    unsafe impl ::allocator_api2::alloc::Allocator for ReusingAlloc {
+        fn allocate(
+            &self,
+            layout: ::allocator_api2::alloc::Layout,
+        ) -> Result<::std::ptr::NonNull<[u8]>, ::allocator_api2::alloc::AllocError> {
+            match self.freed.take() {
+                Some((ptr, freed_layout)) if freed_layout == layout => Ok(
+                    ::std::ptr::NonNull::slice_from_raw_parts(ptr, layout.size()),
+                ),
+                freed => {
+                    self.freed.set(freed);
+                    Global.allocate(layout)
+                }
+            }
+        }
+
+        unsafe fn deallocate(
+            &self,
+            ptr: ::std::ptr::NonNull<u8>,
+            layout: ::allocator_api2::alloc::Layout,
+        ) {
+            if let Some((ptr, layout)) = self.freed.replace(Some((ptr, layout))) {
+                unsafe { Global.deallocate(ptr, layout) }
+            }
+        }
+    }
+
+    impl Drop for ReusingAlloc {
+        fn drop(&mut self) {
+            if ::std::rc::Rc::strong_count(&self.freed) == 1 {
+                if let Some((ptr, layout)) = self.freed.take() {
+                    unsafe { ::allocator_api2::alloc::Allocator::deallocate(&Global, ptr, layout) }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "stale NodeId")]
+    fn test_node_id_stale() {
+        let mut list = OnceList::<i32, ReusingAlloc>::new_in(ReusingAlloc::default());
+        list.push(1);
+        let id = list.push_back_node(2).id();
+        assert_eq!(list.remove_node(id), Some(2));
+        // The new node reuses the removed node's memory.
+        let new_id = list.push_back_node(3).id();
+        assert_eq!(new_id, id);
+        let _ = list.get_by_id(id);
+    }
+
+    #[test]
+    fn test_raw_parts() {
+        let (head, alloc) = OnceList::<String>::new().into_raw_parts();
+        assert!(head.is_none());
+        let list = unsafe { OnceListWithTailLen::<String>::from_raw_parts(head, alloc) };
+        assert!(list.is_empty());
+
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithTailLen::<String, _>::new_in(alloc.clone());
+        list.extend(["a", "b"].map(String::from));
+        let (head, raw_alloc) = list.into_raw_parts();
+        assert!(head.is_some());
+        assert_eq!(alloc.live.get(), 2);
+
+        let list = unsafe { OnceListWithTailLen::<String, _>::from_raw_parts(head, raw_alloc) };
+        assert_eq!(list.len(), 2);
+        list.push_back("c".to_string());
+        assert_eq!(list.len(), 3);
+        assert_eq!(list, ["a", "b", "c"]);
+        drop(list);
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn test_node_iter_after() {
+        let log = OnceList::<i32>::new();
+        let mut last_seen = log.push_back_node(0);
+        let mut poll = || {
+            let new = last_seen.iter_after().copied().collect::<Vec<_>>();
+            while let Some(next) = last_seen.next() {
+                last_seen = next;
+            }
+            new
+        };
+        assert_eq!(poll(), Vec::<i32>::new());
+
+        log.extend([1, 2]);
+        assert_eq!(poll(), vec![1, 2]);
+        log.push_back(3);
+        assert_eq!(poll(), vec![3]);
+        assert_eq!(poll(), Vec::<i32>::new());
+
+        // The iterator itself observes the values pushed after it is exhausted.
+        let mut iter = log.front_node().unwrap().iter_after();
+        assert_eq!(iter.by_ref().count(), 3);
+        log.push_back(4);
+        assert_eq!(iter.next(), Some(&4));
+    }
+
+    #[test]
+    fn test_pinned() {
+        use ::std::marker::PhantomPinned;
+        use ::std::pin::Pin;
+
+        struct SelfAddr {
+            addr: ::std::cell::Cell<usize>,
+            _pin: PhantomPinned,
+        }
+        impl SelfAddr {
+            fn new() -> Self {
+                Self {
+                    addr: ::std::cell::Cell::new(0),
+                    _pin: PhantomPinned,
+                }
+            }
+            fn init(self: Pin<&Self>) {
+                self.addr.set(::std::ptr::from_ref(&*self).addr());
+            }
+            fn is_unmoved(&self) -> bool {
+                self.addr.get() == ::std::ptr::from_ref(self).addr()
+            }
+        }
+
+        let mut list = OnceListWithTailLen::<SelfAddr>::new().into_pinned();
+        for _ in 0..3 {
+            list.push_pinned(SelfAddr::new()).init();
+        }
+        list.push_back(SelfAddr::new());
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().filter(|v| v.is_unmoved()).count(), 3);
+        for v in list.iter_pinned() {
+            v.init();
+        }
+        assert_eq!(list.iter_mut_pinned().count(), 4);
+        assert!(list.iter().all(|v| v.is_unmoved()));
+        list.clear();
+        assert!(list.is_empty());
+
+        let list = OnceList::from([1, 2]).into_pinned();
+        assert_eq!(*list.push_pinned(3), 3);
+        assert_eq!(list.into_inner(), [1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_extend_from_growing_other() {
+        const LEN: usize = if cfg!(miri) { 20 } else { 10_000 };
+        let other = OnceList::<usize>::new();
+        other.extend(0..10);
+        let list = OnceList::<usize>::new();
+        ::std::thread::scope(|s| {
+            s.spawn(|| other.extend(10..LEN));
+            list.extend_from_other(&other);
+        });
+        // The appended values are a prefix of the other list, including the initial values.
+        assert!(list.len() >= 10);
+        assert!(list.iter().eq(other.iter().take(list.len())));
+    }
+
+    #[test]
+    fn test_relocate_in() {
+        let list = OnceListWithTailLen::<String>::new();
+        list.extend(["a", "b", "c"].map(String::from));
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = list.relocate_in(alloc.clone());
+        assert_eq!(alloc.live.get(), 3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().map(String::as_str).collect::<String>(), "abc");
+        // The cache mode is kept.
+        let _: &OnceListWithTailLen<String, FailingAlloc> = &list;
+
+        let other = FailingAlloc::new(usize::MAX);
+        let list = list.relocate_in(other.clone());
+        assert_eq!(alloc.live.get(), 0);
+        assert_eq!(other.live.get(), 3);
+        list.push_back("d".to_string());
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+        assert_eq!(other.live.get(), 0);
+    }
+
+    #[test]
+    fn test_clone_in() {
+        let list = OnceListWithTailLen::<String>::new();
+        list.extend(["a", "b"].map(String::from));
+        let alloc = FailingAlloc::new(usize::MAX);
+        let cloned = list.clone_in(alloc.clone());
+        assert_eq!(alloc.live.get(), 2);
+        // The cache mode is kept, and the original list is untouched.
+        let _: &OnceListWithTailLen<String, FailingAlloc> = &cloned;
+        assert_eq!(cloned.len(), 2);
+        assert_eq!(list.len(), 2);
 
-        assert_eq!(list.remove(|&v| v == 0), None);
-        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+        cloned.push_back("c".to_string());
+        assert_eq!(cloned.iter().map(String::as_str).collect::<String>(), "abc");
+        drop(cloned);
+        assert_eq!(alloc.live.get(), 0);
+    }
 
-        assert_eq!(list.remove(|&v| v == 1), Some(1));
-        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3]);
+    #[test]
+    fn test_map() {
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithTailLen::<i32, _>::new_in(alloc.clone());
+        list.extend([1, 2, 3]);
+        let list = list.map(|v| v.to_string());
+        // The allocator and the cache mode are kept.
+        let _: &OnceListWithTailLen<String, FailingAlloc> = &list;
+        assert_eq!(alloc.live.get(), 3);
+        assert_eq!(list.len(), 3);
 
-        assert_eq!(list.remove(|&v| v == 3), Some(3));
+        list.push_back("4".to_string());
+        assert_eq!(list.iter().map(String::as_str).collect::<String>(), "1234");
+        let list = OnceList::<i32>::new().map(|v| v * 2);
         assert!(list.is_empty());
-    });
+    }
 
-    test_all_i32_variants!(fn test_iter_sees_push_after_exhausted(list) {
-        list.push_back(1);
+    #[test]
+    fn test_filter_map() {
+        use ::std::cell::Cell;
+        use ::std::rc::Rc;
 
-        let mut it = list.iter();
-        assert_eq!(it.next(), Some(&1));
-        assert_eq!(it.next(), None);
+        let drops = Rc::new(Cell::new(0));
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithTailLen::<DropFlag, _>::new_in(alloc.clone());
+        list.extend((0..5).map(|i| DropFlag(i, drops.clone())));
+        let list = list.filter_map(|v| (v.0 % 2 == 0).then_some(v.0));
+        // The filtered out values are dropped, and their nodes are freed.
+        assert_eq!(drops.get(), 5);
+        assert_eq!(alloc.live.get(), 3);
+        assert_eq!(list.len(), 3);
 
-        // After the iterator reached the end, pushing a new element should make it visible
-        // from the same iterator.
-        list.push_back(2);
-        assert_eq!(it.next(), Some(&2));
-        assert_eq!(it.next(), None);
-    });
+        list.push_back(5);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![0, 2, 4, 5]);
+    }
 
-    test_all_i32_variants!(fn test_iter_sees_extend_after_exhausted(list) {
-        list.push_back(1);
+    #[test]
+    fn test_unzip() {
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithTailLen::<(i32, String), _>::new_in(alloc.clone());
+        let (nums, strs) = list.unzip();
+        assert!(nums.is_empty());
+        assert!(strs.is_empty());
 
-        let mut it = list.iter();
-        assert_eq!(it.next(), Some(&1));
-        assert_eq!(it.next(), None);
+        let list = OnceListWithTailLen::<(i32, String), _>::new_in(alloc.clone());
+        list.extend((1..=3).map(|i| (i, i.to_string())));
+        let (nums, strs) = list.unzip();
+        // The allocator and the cache mode are kept.
+        let _: &OnceListWithTailLen<i32, FailingAlloc> = &nums;
+        assert_eq!(alloc.live.get(), 6);
+        assert_eq!(nums.len(), 3);
+        assert_eq!(strs.len(), 3);
 
-        // Same property should hold for `extend()` as well.
-        list.extend([2, 3]);
-        assert_eq!(it.next(), Some(&2));
-        assert_eq!(it.next(), Some(&3));
-        assert_eq!(it.next(), None);
-    });
+        nums.push_back(4);
+        strs.push_back("4".to_string());
+        assert_eq!(nums.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(strs.iter().map(String::as_str).collect::<String>(), "1234");
+    }
 
-    test_all_i32_variants!(fn test_iter_mut_allows_in_place_update(list) {
-        let mut list = list;
-        list.extend([1, 2, 3]);
-        for v in list.iter_mut() {
-            *v += 10;
+    #[test]
+    fn test_flatten() {
+        let list = OnceList::<OnceListWithTailLen<i32>>::new();
+        assert!(list.flatten().is_empty());
+
+        let list = OnceList::<OnceListWithTailLen<i32>>::new();
+        for range in [0..3, 3..3, 3..4, 4..6] {
+            let inner = OnceListWithTailLen::new();
+            inner.extend(range);
+            list.push_back(inner);
         }
-        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 12, 13]);
-    });
+        let first = list.front().unwrap().front().unwrap() as *const i32;
+        let flat = list.flatten();
+        assert_eq!(flat.len(), 6);
+        // The node is moved, not the value.
+        assert_eq!(flat.front().unwrap() as *const i32, first);
 
-    test_all_i32_variants!(fn test_into_iter_for_ref(list) {
+        flat.push_back(6);
+        assert_eq!(
+            flat.into_iter().collect::<Vec<_>>(),
+            (0..7).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_zip_with() {
+        let alloc = FailingAlloc::new(usize::MAX);
+        let list = OnceListWithTailLen::<i32, _>::new_in(alloc.clone());
         list.extend([1, 2, 3]);
+        let other = OnceList::<i32>::new();
+        other.extend([10, 20, 30, 40]);
+        let list = list.zip_with(other, |a, b| (a + b).to_string());
+        // The allocator and the cache mode are kept.
+        let _: &OnceListWithTailLen<String, FailingAlloc> = &list;
+        assert_eq!(alloc.live.get(), 3);
+        assert_eq!(list.len(), 3);
+        list.push_back("x".to_string());
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            vec!["11", "22", "33", "x"]
+        );
 
-        // `IntoIterator for &OnceListCore` should yield `&T`.
-        let collected = (&list).into_iter().copied().collect::<Vec<_>>();
-        assert_eq!(collected, vec![1, 2, 3]);
+        let list = OnceList::<i32>::new().zip_with(OnceList::<i32>::from([1]), |a, b| a + b);
+        assert!(list.is_empty());
+    }
 
-        // Also ensure `for x in &list` works (uses the same `IntoIterator` impl).
-        let mut sum = 0;
-        for &v in &list {
-            sum += v;
-        }
-        assert_eq!(sum, 6);
-    });
+    #[test]
+    fn test_refresh_caches() {
+        let mut list = OnceListWithTailLen::<i32>::new();
+        list.refresh_caches();
+        assert!(list.cache_mode.tail_slot_opt(&list.head_slot).is_none());
+        assert_eq!(list.len(), 0);
 
-    test_all_i32_variants!(fn test_into_iter_for_mut_ref_allows_in_place_update(list) {
-        let mut list = list;
-        list.extend([1, 2, 3]);
+        list.extend(0..1000);
+        // Mutable accesses make the tail cache cold.
+        list.iter_mut().for_each(|v| *v += 1);
+        assert!(list.cache_mode.tail_slot_opt(&list.head_slot).is_none());
 
-        // `IntoIterator for &mut OnceListCore` should yield `&mut T`.
-        for v in &mut list {
-            *v += 10;
-        }
-        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 12, 13]);
-    });
+        list.refresh_caches();
+        list.refresh_caches();
+        // The push right after the refresh does not walk from the head.
+        let tail_slot = list
+            .cache_mode
+            .tail_slot_opt(&list.head_slot)
+            .map(|s| s as *const _);
+        assert_eq!(
+            tail_slot,
+            list.iter_from(999)
+                .next_slot
+                .get()
+                .map(|b| &b.next as *const _)
+        );
+        assert_eq!(list.len(), 1000);
+        list.push_back(1001);
+        assert_eq!(list.back(), Some(&1001));
+        assert_eq!(list.len(), 1001);
 
-    test_all_i32_variants!(fn test_iter_mut_empty_and_singleton(list) {
-        // Empty list
-        {
-            let mut empty = list;
-            let mut it = empty.iter_mut();
-            assert!(it.next().is_none());
+        let list = OnceListWithLen::<i32>::new();
+        list.extend([1, 2]);
+        list.refresh_caches();
+        assert_eq!(list.len(), 2);
 
-            // Singleton list (reuse the same list type/instance)
-            empty.push_back(1);
-            let mut it = empty.iter_mut();
-            let v = it.next().unwrap();
-            *v = 2;
-            assert!(it.next().is_none());
-            assert_eq!(empty.into_iter().collect::<Vec<_>>(), vec![2]);
-        }
-    });
+        let list = OnceList::<i32>::new();
+        list.refresh_caches();
+        assert!(list.is_empty());
+    }
 
-    test_all_i32_variants!(fn test_eq(list1) {
-        list1.extend([1, 2, 3]);
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_first_or_push_race() {
+        let list = OnceList::<usize>::new();
+        let firsts = ::std::thread::scope(|s| {
+            let handles = (0..4)
+                .map(|t| {
+                    let list = &list;
+                    s.spawn(move || *list.first_or_push(|| t))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        // Every thread sees the same winner, and only it is pushed.
+        assert!(firsts.iter().all(|&v| v == firsts[0]));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.front(), Some(&firsts[0]));
+    }
 
-        let list2 = {
-            let l = OnceList::<i32>::new();
-            l.extend([1, 2, 3]);
-            l
-        };
-        assert_eq!(
-            list1.iter().collect::<Vec<_>>(),
-            list2.iter().collect::<Vec<_>>()
-        );
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_push_unique_race() {
+        const VALUES: usize = if cfg!(miri) { 10 } else { 200 };
+        let list = OnceList::<usize>::new();
+        ::std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for v in 0..VALUES {
+                        assert_eq!(list.push_unique(v), &v);
+                    }
+                });
+            }
+        });
+        // Each value is pushed exactly once.
+        let mut values = list.into_iter().collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values, (0..VALUES).collect::<Vec<_>>());
+    }
 
-        let list3 = {
-            let l = OnceList::<i32>::new();
-            l.extend([1, 2, 4]);
-            l
-        };
-        assert_ne!(
-            list1.iter().collect::<Vec<_>>(),
-            list3.iter().collect::<Vec<_>>()
-        );
-    });
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_find_or_push_race() {
+        const KEYS: usize = if cfg!(miri) { 10 } else { 200 };
+        let list = OnceList::<(usize, usize)>::new();
+        ::std::thread::scope(|s| {
+            for t in 0..4 {
+                let list = &list;
+                s.spawn(move || {
+                    for k in 0..KEYS {
+                        assert_eq!(list.find_or_push(|&(k2, _)| k2 == k, || (k, t)).0, k);
+                    }
+                });
+            }
+        });
+        // Only one value is pushed for each key.
+        let mut keys = list.iter().map(|&(k, _)| k).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, (0..KEYS).collect::<Vec<_>>());
+    }
 
-    test_all_i32_variants!(fn test_hash(list1) {
-        use ::std::hash::{DefaultHasher, Hasher};
-        list1.extend([1, 2, 3]);
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_get_or_extend_with_race() {
+        let list = OnceList::<usize>::new();
+        ::std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| assert_eq!(list.get_or_extend_with(|| 0..100).count(), 100));
+            }
+        });
+        // Only one thread's values are appended.
+        assert!(list.iter().copied().eq(0..100));
+    }
 
-        let list2 = {
-            let l = OnceList::<i32>::new();
-            l.extend([1, 2, 3]);
-            l
-        };
+    #[test]
+    fn test_collect_result() {
+        let list = OnceListWithTailLen::<Result<i32, &str>>::new();
+        list.extend([Ok(1), Ok(2), Ok(3)]);
+        let collected = list.collect_result().unwrap();
+        assert_eq!(collected.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
 
-        let mut hasher1 = DefaultHasher::new();
-        let mut hasher2 = DefaultHasher::new();
-        list1.hash(&mut hasher1);
-        list2.hash(&mut hasher2);
-        assert_eq!(hasher1.finish(), hasher2.finish());
-    });
+        let list = OnceList::<Result<i32, &str>>::new();
+        list.extend([Ok(1), Err("first"), Ok(3), Err("second")]);
+        assert_eq!(list.collect_result().err(), Some("first"));
+
+        let list = OnceList::<Result<i32, &str>>::new();
+        assert!(list.collect_result().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_listeners() {
+        use ::std::sync::Mutex;
+
+        let events = ::std::sync::Arc::new(Mutex::new(Vec::new()));
+        let list = OnceListWithTail::<dyn Fn(&str) + Send + Sync>::new();
+        list.notify_all("ignored");
+        for i in 0..3 {
+            let events = ::std::sync::Arc::clone(&events);
+            list.push_listener(move |e: &str| events.lock().unwrap().push(format!("{i}:{e}")));
+        }
+        let last = list.push_listener(|_: &str| {});
+        last("not recorded");
+
+        list.notify_all("a");
+        assert_eq!(*events.lock().unwrap(), vec!["0:a", "1:a", "2:a"]);
+        assert_eq!(list.len(), 4);
+    }
 
     #[test]
     #[cfg(feature = "nightly")]
@@ -355,6 +2670,23 @@ mod tests {
         assert_eq!(list.iter().nth(1), Some(&[2, 3] as &[i32]));
     }
 
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_unsized_extend() {
+        let list: OnceListWithTailLen<[i32]> = OnceListWithTailLen::new();
+        list.push_unsized([0]);
+        list.extend_unsized((1..300).map(|i| [i, i]));
+        assert_eq!(list.len(), 300);
+        assert!(list.iter().skip(1).zip(1..).all(|(s, i)| s == [i, i]));
+
+        let list: OnceList<dyn ToString> = OnceList::new();
+        list.extend_unsized([1, 2, 3]);
+        assert_eq!(
+            list.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+            vec!["1", "2", "3"]
+        );
+    }
+
     #[test]
     #[cfg(feature = "nightly")]
     fn test_unsized_dyn_push() {
@@ -374,6 +2706,24 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_into_unsized() {
+        let list: OnceListWithTailLen<i32> = OnceListWithTailLen::new();
+        list.extend([1, 2, 3]);
+        let list: OnceList<dyn ::std::fmt::Debug> = list.into_unsized();
+        assert_eq!(
+            list.iter().map(|v| format!("{:?}", v)).collect::<Vec<_>>(),
+            vec!["1", "2", "3"]
+        );
+        list.push_unsized("four");
+        assert_eq!(format!("{:?}", list), r#"[1, 2, 3, "four"]"#);
+
+        let list: OnceList<dyn ::std::fmt::Debug> = OnceList::<String>::new().into_unsized();
+        assert!(list.is_empty());
+        assert_eq!(list.iter().next().map(|_| ()), None);
+    }
+
     #[test]
     #[cfg(feature = "nightly")]
     fn test_unsized_slice_remove_into_box() {
@@ -428,6 +2778,248 @@ mod tests {
         assert_eq!(list.iter().nth(1), Some(&[4, 5, 6] as &[i32]));
     }
 
+    #[test]
+    fn test_remove_by_value_positions() {
+        use ::allocator_api2::boxed::Box;
+
+        // Removes the head, the tail and the middle values, in this order.
+        let mut list = OnceListWithTailLen::<dyn ::std::any::Any>::new();
+        list.push_any(1_u8);
+        list.push_any("two".to_string());
+        list.push_any([3_u64; 3]);
+        list.push_any(4_i32);
+        assert_eq!(list.remove_by_type::<u8>(), Some(1));
+        assert_eq!(list.remove_by_type::<i32>(), Some(4));
+        assert_eq!(list.remove_by_type::<String>(), Some("two".to_string()));
+        assert_eq!(list.remove_by_type::<[u64; 3]>(), Some([3; 3]));
+        assert!(list.is_empty());
+        list.push_any(5_i32);
+        assert_eq!(list.remove_by_type::<i32>(), Some(5));
+
+        let mut list = OnceListWithTailLen::<[String]>::new();
+        for s in [&["a"][..], &["b", "c"], &[], &["d", "e", "f"]] {
+            list.push_boxed(Box::from_iter(s.iter().map(|s| s.to_string())));
+        }
+        assert_eq!(list.remove_array::<1>(), Some(["a".to_string()]));
+        let tail = ["d".to_string(), "e".to_string(), "f".to_string()];
+        assert_eq!(list.remove_array::<3>(), Some(tail));
+        assert_eq!(list.remove_array::<0>(), Some([]));
+        assert_eq!(list.len(), 1);
+        assert_eq!(
+            list.pop_front_boxed().as_deref(),
+            Some(&["b".to_string(), "c".to_string()][..])
+        );
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_remove_unsized_as_positions() {
+        let mut list = OnceListWithTail::<[String]>::new();
+        list.push_unsized(["a".to_string()]);
+        list.push_unsized(["b".to_string(), "c".to_string()]);
+        list.push_unsized(["d".to_string()]);
+        list.push_unsized(["e".to_string(), "f".to_string(), "g".to_string()]);
+
+        let head: Option<[String; 1]> = unsafe { list.remove_unsized_as(|s| s.try_into().ok()) };
+        assert_eq!(head, Some(["a".to_string()]));
+        let tail: Option<[String; 3]> = unsafe { list.remove_unsized_as(|s| s.try_into().ok()) };
+        assert_eq!(tail.map(|a| a.concat()), Some("efg".to_string()));
+        list.push_unsized(["h".to_string()]);
+        let middle: Option<[String; 1]> = unsafe { list.remove_unsized_as(|s| s.try_into().ok()) };
+        assert_eq!(middle, Some(["d".to_string()]));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_set_by_type() {
+        let mut list = OnceListWithTailLen::<dyn ::std::any::Any>::new();
+        // Insert when absent.
+        assert_eq!(list.set_by_type(1_i32), None);
+        list.push_any("a");
+        list.push_any(2_u8);
+        list.push_any(3_i32);
+        assert_eq!(list.set_by_type(4_u64), None);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.back().unwrap().downcast_ref::<u64>(), Some(&4));
+
+        // Replace when present, keeping the position. Only the first `i32` is replaced.
+        assert_eq!(list.set_by_type(10_i32), Some(1));
+        assert_eq!(list.set_by_type("b"), Some("a"));
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.iter().next().unwrap().downcast_ref::<i32>(), Some(&10));
+        assert_eq!(
+            list.iter().nth(1).unwrap().downcast_ref::<&str>(),
+            Some(&"b")
+        );
+        assert_eq!(list.iter().nth(3).unwrap().downcast_ref::<i32>(), Some(&3));
+
+        list.push_any(());
+        assert_eq!(list.len(), 6);
+        assert!(list.back().unwrap().is::<()>());
+    }
+
+    test_all_i32_variants!(fn test_eq_with_seq(list) {
+        assert_eq!(list, []);
+        assert_eq!(list, Vec::<i32>::new());
+        list.extend([1, 2, 3]);
+        assert_eq!(list, [1, 2, 3]);
+        assert_eq!(list, vec![1, 2, 3]);
+        assert_eq!(list, [1, 2, 3][..]);
+        assert_eq!([1, 2, 3], list);
+        assert_eq!(vec![1, 2, 3], list);
+        assert_eq!([1, 2, 3][..], list);
+        assert_ne!(list, [1, 2]);
+        assert_ne!(list, [1, 2, 3, 4]);
+        assert_ne!(vec![1, 2, 4], list);
+    });
+
+    #[test]
+    fn test_eq_with_seq_of_other_type() {
+        let list = OnceList::<String>::new();
+        list.extend(["a".to_string(), "b".to_string()]);
+        assert_eq!(list, ["a", "b"]);
+        assert_eq!(vec!["a", "b"], list);
+    }
+
+    #[test]
+    fn test_ord() {
+        use ::std::cmp::Ordering;
+
+        let empty = OnceList::<i32>::new();
+        let short = OnceList::from([1, 2]);
+        let long = OnceList::from([1, 2, 3]);
+        let big = OnceList::from([1, 3]);
+        assert_eq!(empty.cmp(&short), Ordering::Less);
+        assert_eq!(short.cmp(&long), Ordering::Less);
+        assert_eq!(long.cmp(&big), Ordering::Less);
+        assert_eq!(big.cmp(&big.clone()), Ordering::Equal);
+        assert!(big > long);
+
+        let nan = OnceList::from([1.0, f64::NAN]);
+        assert_eq!(nan.partial_cmp(&OnceList::from([1.0, 2.0])), None);
+        let one = OnceList::from([1.0]);
+        let two = OnceList::from([2.0]);
+        assert!(one < two);
+
+        let mut lists = [big, long, empty, short];
+        lists.sort();
+        assert_eq!(lists[0], []);
+        assert_eq!(lists[1], [1, 2]);
+        assert_eq!(lists[2], [1, 2, 3]);
+        assert_eq!(lists[3], [1, 3]);
+    }
+
+    test_all_i32_variants!(fn test_join(list) {
+        assert_eq!(list.join(", "), "");
+        list.push_back(1);
+        assert_eq!(list.join(", "), "1");
+        list.extend([2, 3]);
+        assert_eq!(list.join(", "), "1, 2, 3");
+        assert_eq!(list.join(""), "123");
+        assert_eq!(format!("<{:>2}>", list.display("|")), "< 1| 2| 3>");
+    });
+
+    #[test]
+    fn test_push_str() {
+        let list = OnceList::<str>::new();
+        let first = list.push_str("hello");
+        let second = list.push_str("");
+        let third = list.push_str("world");
+        assert_eq!((first, second, third), ("hello", "", "world"));
+
+        assert!(list.contains("hello"));
+        assert!(list.contains(""));
+        assert!(!list.contains("hell"));
+        assert_eq!(list.iter().find(|s| s.starts_with('w')), Some("world"));
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_extend_strs() {
+        let list = OnceListWithTail::<str>::new();
+        list.push_str("first");
+        list.extend_strs((0..300).map(|i| i.to_string()));
+        list.extend_strs(["last"]);
+        assert_eq!(list.len(), 302);
+        assert_eq!(list.front(), Some("first"));
+        assert_eq!(list.back(), Some("last"));
+        assert!(list
+            .iter()
+            .skip(1)
+            .take(300)
+            .zip(0..)
+            .all(|(s, i)| s == i.to_string()));
+    }
+
+    #[test]
+    fn test_str_clone() {
+        let list = OnceListWithTailLen::<str>::new();
+        list.extend_strs(["a", "", "hello", "world!"]);
+
+        let mut cloned = list.clone();
+        assert_eq!(cloned, list);
+        assert_eq!(cloned.len(), 4);
+
+        for s in cloned.iter_mut() {
+            s.make_ascii_uppercase();
+        }
+        cloned.push_str("new");
+        assert_eq!(
+            cloned.iter().collect::<Vec<_>>(),
+            vec!["A", "", "HELLO", "WORLD!", "new"]
+        );
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            vec!["a", "", "hello", "world!"]
+        );
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_push_path_and_os_str() {
+        use ::std::ffi::OsStr;
+        use ::std::path::Path;
+
+        let paths = OnceListWithTailLen::<Path>::new();
+        paths.push_path(Path::new("/usr/bin"));
+        paths.push_path(Path::new("/usr/local/bin"));
+        assert!(paths.contains(Path::new("/usr/local/bin")));
+        assert!(!paths.contains(Path::new("/bin")));
+        assert_eq!(
+            paths
+                .iter()
+                .find(|p| p.ends_with("bin") && p.starts_with("/usr/local")),
+            Some(Path::new("/usr/local/bin"))
+        );
+        assert_eq!(paths.len(), 2);
+
+        let os_strs = OnceList::<OsStr>::new();
+        os_strs.push_os_str(OsStr::new("foo"));
+        os_strs.push_os_str(OsStr::new("bar"));
+        assert!(os_strs.contains(OsStr::new("bar")));
+        assert_eq!(
+            os_strs.iter().map(|s| s.to_str()).collect::<Vec<_>>(),
+            vec![Some("foo"), Some("bar")]
+        );
+    }
+
+    #[test]
+    fn test_push_c_str() {
+        use ::std::ffi::CStr;
+
+        let list = OnceList::<CStr>::new();
+        let hello = list.push_c_str(c"hello");
+        list.push_c_str(c"");
+        assert_eq!(hello.to_bytes_with_nul(), b"hello\0");
+        assert!(list.contains(c""));
+        assert_eq!(
+            list.iter().find(|s| s.to_bytes() == b"hello"),
+            Some(c"hello")
+        );
+        assert_eq!(list.iter().nth(1).map(|s| s.count_bytes()), Some(0));
+    }
+
     // (No special "variant labeling" test needed; the per-variant generic `run::<L>()`
     // monomorphization will show `L` in backtraces when `RUST_BACKTRACE=1` is enabled.)
 }
@@ -0,0 +1,88 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::allocator_api2::alloc::Allocator;
+use ::allocator_api2::boxed::Box;
+
+use crate::cache_mode::CacheMode;
+use crate::cons::Cons;
+use crate::once_list::OnceListCore;
+
+/// A listener closure type stored in the list.
+type Listener<E> = dyn Fn(&E) + Send + Sync;
+
+impl<E: ?Sized, A: Allocator + Clone, M> OnceListCore<Listener<E>, A, M>
+where
+    M: CacheMode<Listener<E>, A>,
+{
+    /// Registers a listener closure to the list, and returns the reference to that listener.
+    ///
+    /// Like the other `push_xxx` methods, this method takes `&self`, so the list can be used as
+    /// a lock-free listener registry.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    /// use std::sync::atomic::{AtomicI32, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let listeners = OnceList::<dyn Fn(&i32) + Send + Sync>::new();
+    /// let sum = Arc::new(AtomicI32::new(0));
+    /// let count = Arc::new(AtomicI32::new(0));
+    /// {
+    ///     let sum = Arc::clone(&sum);
+    ///     listeners.push_listener(move |e: &i32| {
+    ///         sum.fetch_add(*e, Ordering::Relaxed);
+    ///     });
+    /// }
+    /// {
+    ///     let count = Arc::clone(&count);
+    ///     listeners.push_listener(move |_: &i32| {
+    ///         count.fetch_add(1, Ordering::Relaxed);
+    ///     });
+    /// }
+    ///
+    /// listeners.notify_all(&10);
+    /// listeners.notify_all(&5);
+    /// assert_eq!(sum.load(Ordering::Relaxed), 15);
+    /// assert_eq!(count.load(Ordering::Relaxed), 2);
+    /// ```
+    pub fn push_listener<F>(&self, f: F) -> &Listener<E>
+    where
+        F: Fn(&E) + Send + Sync + 'static,
+    {
+        let sized_box = Box::new_in(Cons::<F, Listener<E>, A>::new(f), A::clone(&self.alloc));
+        // Same as `push_any`, we need to manually do the unsized coercion for the non-standard `Box`.
+        let unsized_box = unsafe {
+            let (sized_ptr, alloc) = Box::into_raw_with_allocator(sized_box);
+            // Pointer unsized coercion!
+            let unsized_ptr: *mut Cons<Listener<E>, Listener<E>, A> = sized_ptr;
+            Box::from_raw_in(unsized_ptr, alloc)
+        };
+        self.push_inner(unsized_box, |f| f)
+    }
+}
+
+impl<E: ?Sized, A: Allocator, M> OnceListCore<Listener<E>, A, M> {
+    /// Calls every listener in the list with the given event, in the registration order.
+    ///
+    /// The listeners registered while this method is running, either from another thread (with
+    /// the `sync` feature) or from a listener itself, may or may not be called for the current
+    /// event: a listener is called if and only if it has been appended before the iteration
+    /// reaches the end of the list.
+    pub fn notify_all(&self, event: &E) {
+        for listener in self.iter() {
+            listener(event);
+        }
+    }
+}
@@ -12,19 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ::allocator_api2::alloc::{Allocator, Global};
+use ::allocator_api2::alloc::{AllocError, Allocator, Global};
 use ::allocator_api2::boxed::Box;
+use ::std::cmp::Ordering;
 use ::std::fmt::Debug;
 use ::std::hash::Hash;
 #[cfg(feature = "nightly")]
 use ::std::marker::Unsize;
-use ::std::ops::DerefMut;
+use ::std::ops::{DerefMut, Index, IndexMut};
+use ::std::ptr::NonNull;
 
-use crate::cache_mode::{CacheMode, NextSlot, NoCache, WithLen, WithTail, WithTailLen};
+use crate::cache_mode::{
+    CacheMode, NextSlot, NoCache, RebindAlloc, WithLen, WithTail, WithTailLen,
+};
 use crate::cons::Cons;
-use crate::iter::{IntoIter, Iter, IterMut};
-#[cfg(feature = "nightly")]
-use crate::OnceCell;
+use crate::error::{IndexError, TryExtendError};
+use crate::iter::{IntoIter, Iter, IterMut, IterMutRev};
 
 /// A single linked list which behaves like [`std::cell::OnceCell`], but for multiple values.
 ///
@@ -98,7 +101,8 @@ pub type OnceListWithTailLen<T, A = Global> = OnceListCore<T, A, WithTailLen<T,
 ///
 /// You can use the [unsized types] like `str`, `[u8]` or `dyn Display` as the value type of the `OnceList`.
 ///
-/// If you are using the stable rust compiler, you can only use the `dyn Any` type as the unsized type.
+/// If you are using the stable rust compiler, you can only use the `dyn Any` type and the string-like
+/// types (`str`, `OsStr`, `Path` and `CStr`, via `push_str` etc.) as the unsized type.
 /// (Strictly speaking, you can use ANY type as the unsized type, but you can't do any actual operations
 /// like pushing, removing, etc.)
 ///
@@ -144,7 +148,6 @@ pub type OnceListWithTailLen<T, A = Global> = OnceListCore<T, A, WithTailLen<T,
 ///
 /// The method implementations live on [`OnceListCore`]. The user-facing type aliases like
 /// [`OnceList`] and [`OnceListWithTail`] point to this type.
-#[derive(Clone)]
 pub struct OnceListCore<T: ?Sized, A: Allocator = Global, C = NoCache> {
     pub(crate) head_slot: NextSlot<T, A>,
     pub(crate) alloc: A,
@@ -238,6 +241,110 @@ impl<T: ?Sized, A: Allocator> OnceListCore<T, A, NoCache> {
     }
 }
 
+impl<T, C> OnceListCore<T, Global, C>
+where
+    C: CacheMode<T, Global> + Default,
+{
+    /// Creates a new list of `n` values, where the value at the index `i` is `f(i)`.
+    ///
+    /// The nodes are linked directly, without any intermediate collection.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::from_fn(4, |i| i * i);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&0, &1, &4, &9]);
+    /// ```
+    pub fn from_fn<F>(n: usize, f: F) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        Self::from_fn_in(n, f, Global)
+    }
+
+    /// Creates a new list of `n` clones of the given value, in the same manner as `vec![val; n]`.
+    ///
+    /// The value is cloned `n - 1` times and moved into the last node. If `n` is 0, the value is
+    /// dropped.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let list = OnceListWithLen::repeat(0u8, 3);
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&0, &0, &0]);
+    /// ```
+    pub fn repeat(val: T, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self::repeat_in(val, n, Global)
+    }
+
+    /// Creates a new list from the iterator of `Result`s, stopping at the first `Err`.
+    ///
+    /// Returns the list of all the `Ok` values if no `Err` is found. Otherwise, returns the
+    /// error together with the list of the `Ok` values before it, so that the caller can decide
+    /// whether to keep or discard the partially built list. The rest of the iterator is not
+    /// consumed.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::try_from_iter(["1", "2"].map(str::parse::<i32>)).unwrap();
+    /// assert_eq!(list, [1, 2]);
+    ///
+    /// let (_err, partial) = OnceList::try_from_iter(["1", "x", "3"].map(str::parse::<i32>))
+    ///     .unwrap_err();
+    /// assert_eq!(partial, [1]);
+    /// ```
+    pub fn try_from_iter<E, U>(iter: U) -> Result<Self, (E, Self)>
+    where
+        U: IntoIterator<Item = Result<T, E>>,
+    {
+        let list = Self::default();
+        match list.try_extend_results(iter) {
+            Ok(()) => Ok(list),
+            Err(e) => Err((e, list)),
+        }
+    }
+}
+
+impl<T, A: Allocator + Clone, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A> + Default,
+{
+    /// Same as [`OnceListCore::from_fn`], but the list allocates its nodes with the given
+    /// allocator.
+    pub fn from_fn_in<F>(n: usize, f: F, alloc: A) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        let list = Self {
+            head_slot: NextSlot::new(),
+            alloc,
+            cache_mode: C::default(),
+        };
+        list.extend((0..n).map(f));
+        list
+    }
+
+    /// Same as [`OnceListCore::repeat`], but the list allocates its nodes with the given
+    /// allocator.
+    pub fn repeat_in(val: T, n: usize, alloc: A) -> Self
+    where
+        T: Clone,
+    {
+        let list = Self {
+            head_slot: NextSlot::new(),
+            alloc,
+            cache_mode: C::default(),
+        };
+        list.extend(::std::iter::repeat_n(val, n));
+        list
+    }
+}
+
 impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
     /// Returns the number of values in the list.
     ///
@@ -266,14 +373,75 @@ impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
         self.iter().any(|v| v == val)
     }
 
+    /// Returns the index of the first value matching the predicate.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3, 2]);
+    /// assert_eq!(list.position(|&v| v == 2), Some(1));
+    /// assert_eq!(list.rposition(|&v| v == 2), Some(3));
+    /// assert_eq!(list.position(|&v| v == 4), None);
+    /// ```
+    pub fn position<P>(&self, pred: P) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().position(pred)
+    }
+
+    /// Returns the index of the last value matching the predicate.
+    ///
+    /// This method walks the whole list once, remembering the last match.
+    pub fn rposition<P>(&self, mut pred: P) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .enumerate()
+            .filter_map(|(i, v)| pred(v).then_some(i))
+            .last()
+    }
+
     /// Returns the front value, if it exists.
     pub fn front(&self) -> Option<&T> {
         self.head_slot.get().map(|c| &c.val)
     }
 
     /// Returns a mutable reference to the front value, if it exists.
-    pub fn front_mut(&mut self) -> Option<&mut T> {
-        self.head_slot.get_mut().map(|c| &mut c.val)
+    pub fn front_mut(&mut self) -> Option<&mut T>
+    where
+        C: CacheMode<T, A>,
+    {
+        self.nodes_mut().get_mut().map(|c| &mut c.val)
+    }
+
+    /// Returns a mutable reference to the front value and a mutable iterator over the rest of
+    /// the values, or `None` if the list is empty.
+    ///
+    /// This is the same as [`slice::split_first_mut`]. The two borrows are disjoint, so they can
+    /// be used at the same time.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// if let Some((first, rest)) = list.split_first_mut() {
+    ///     for v in rest {
+    ///         *first += *v;
+    ///         *v = 0;
+    ///     }
+    /// }
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&6, &0, &0]);
+    /// ```
+    pub fn split_first_mut(&mut self) -> Option<(&mut T, IterMut<'_, T, A>)>
+    where
+        C: CacheMode<T, A>,
+    {
+        let head_cons: &mut Cons<T, T, A> = self.nodes_mut().get_mut()?;
+        Some((&mut head_cons.val, IterMut::new(&mut head_cons.next)))
     }
 
     /// Returns the back value, if it exists.
@@ -290,9 +458,12 @@ impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
 
     /// Returns a mutable reference to the back value, if it exists.
     /// This method is O(n).
-    pub fn back_mut(&mut self) -> Option<&mut T> {
+    pub fn back_mut(&mut self) -> Option<&mut T>
+    where
+        C: CacheMode<T, A>,
+    {
         let mut last_opt = None;
-        let mut next_cell = &mut self.head_slot;
+        let mut next_cell = self.nodes_mut();
         while let Some(next_box) = next_cell.get_mut() {
             let next_cons = Box::deref_mut(next_box);
             last_opt = Some(&mut next_cons.val);
@@ -311,7 +482,10 @@ impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
     /// Returns a mutable reference to the front value, if it exists.
     ///
     /// This is an alias of [`OnceListCore::front_mut`].
-    pub fn first_mut(&mut self) -> Option<&mut T> {
+    pub fn first_mut(&mut self) -> Option<&mut T>
+    where
+        C: CacheMode<T, A>,
+    {
         self.front_mut()
     }
 
@@ -325,251 +499,2690 @@ impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
     /// Returns a mutable reference to the back value, if it exists.
     ///
     /// This is an alias of [`OnceListCore::back_mut`].
-    pub fn last_mut(&mut self) -> Option<&mut T> {
+    pub fn last_mut(&mut self) -> Option<&mut T>
+    where
+        C: CacheMode<T, A>,
+    {
         self.back_mut()
     }
 
-    /// Returns an iterator over the `&T` references in the list.
-    pub fn iter(&self) -> Iter<'_, T, A> {
-        Iter::new(&self.head_slot)
-    }
-
-    /// Returns an iterator over the `&mut T` references in the list.
-    pub fn iter_mut(&mut self) -> IterMut<'_, T, A> {
-        IterMut::new(&mut self.head_slot)
-    }
-
-    /// Returns an allocator of this struct.
-    pub fn allocator(&self) -> &A {
-        &self.alloc
-    }
-}
-
-impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C>
-where
-    C: CacheMode<T, A>,
-{
-    /// Clears the list, dropping all values.
-    pub fn clear(&mut self) {
-        self.head_slot = NextSlot::new();
-        self.cache_mode.on_clear();
-        self.cache_mode.on_structure_change();
+    /// Returns the reference to the value at the given index, or `None` if the index is out of
+    /// range.
+    ///
+    /// This method is O(index).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.get(1), Some(&2));
+    /// assert_eq!(list.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.iter().nth(index)
     }
-}
 
-impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C>
-where
-    C: CacheMode<T, A>,
-{
-    /// Removes the first value in the list that matches the predicate, and returns the value as a boxed value.
-    ///
-    /// This method supports the unsized value type `T` as well.
+    /// Returns the mutable reference to the value at the given index, or `None` if the index is
+    /// out of range.
     ///
-    /// Note that even though this method returns a boxed value, the box is something re-allcoated.
-    /// So this method might not be efficient as you expect.
-    #[cfg(feature = "nightly")]
-    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
-    pub fn remove_into_box<P>(&mut self, pred: P) -> Option<Box<T, A>>
+    /// This method is O(index).
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T>
     where
-        P: FnMut(&T) -> bool,
+        C: CacheMode<T, A>,
     {
-        self.remove_inner(pred, |boxed_cons| boxed_cons.box_into_inner_box())
+        self.nth_mut(index)
     }
 
-    /// Removes the first value in the list that matches the predicate, and returns the value.
+    /// Returns the mutable references to the values at the given indices at once, in a single
+    /// walk over the list.
     ///
-    /// The predicate function `pred` should return `Some(&U)` if the value is found,
-    /// and the returned reference `&U` must be the same address as the value given in the `pred`.
+    /// Each element of the result is `None` if the corresponding index is out of range. This
+    /// method is O(max index * N).
     ///
-    /// # Safety
-    /// This method is unsafe because it requires the predicate to return a reference to the same address as the value.
-    #[cfg(feature = "nightly")]
-    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
-    pub unsafe fn remove_unsized_as<P, U>(&mut self, mut pred: P) -> Option<U>
+    /// # Panics
+    ///
+    /// Panics if the indices contain duplicates.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// if let [Some(a), Some(b), None] = list.get_disjoint_mut([2, 0, 5]) {
+    ///     std::mem::swap(a, b);
+    /// }
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+    /// ```
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [usize; N]) -> [Option<&mut T>; N]
     where
-        P: FnMut(&T) -> Option<&U>,
+        C: CacheMode<T, A>,
     {
-        use ::allocator_api2::alloc;
-        use ::std::cell::Cell;
-
-        let found_sized_ptr: Cell<Option<*const U>> = Cell::new(None);
-        self.remove_inner(
-            |val| {
-                if let Some(val) = pred(val) {
-                    found_sized_ptr.set(Some(val as *const U));
-                    true
-                } else {
-                    false
-                }
-            },
-            |boxed_cons| -> U {
-                // Given the boxed cons with the unsized value type `T`,
-                // and returns the sized type value `U` by value (i.e. out of the box).
-
-                // We are sure the `found_sized_ptr` is set when `remove_inner` calls this closure.
-                let found_sized_ptr: *const U = match found_sized_ptr.get() {
-                    Some(p) => p,
-                    None => unreachable!("remove_unsized_as: missing found pointer"),
-                };
-
-                let cons_layout = alloc::Layout::for_value::<Cons<T, T, A>>(&boxed_cons);
-                let (cons_ptr, alloc) = Box::into_non_null_with_allocator(boxed_cons);
-                let val_ptr = &unsafe { cons_ptr.as_ref() }.val as *const T;
-
-                // Double check the ptr returned by the `pred` is the same as the pointer we extracted from the cons.
-                debug_assert_eq!(val_ptr as *const U, found_sized_ptr);
-
-                // Load (memcpy) the value into the output variable.
-                let result = unsafe { ::std::ptr::read(val_ptr as *const U) };
-
-                // Free the cons memory.
-                unsafe { alloc.deallocate(cons_ptr.cast(), cons_layout) };
+        for (k, index) in indices.iter().enumerate() {
+            if indices[..k].contains(index) {
+                panic!("get_disjoint_mut: the index {index} is duplicated");
+            }
+        }
 
-                result
-            },
-        )
+        let mut found = [const { None }; N];
+        let Some(&max) = indices.iter().max() else {
+            return found;
+        };
+        for (i, val) in self.iter_mut().enumerate().take(max.saturating_add(1)) {
+            if let Some(k) = indices.iter().position(|&index| index == i) {
+                found[k] = Some(val);
+            }
+        }
+        found
     }
 
-    /// An inner implementeation for `remove_xxx` methods.
-    pub(crate) fn remove_inner<P, F, U>(&mut self, mut pred: P, mut f: F) -> Option<U>
+    /// Returns the reference to the value at the given index, or an [`IndexError`] if the index
+    /// is out of range.
+    ///
+    /// This method is O(index), plus O(n) to compute the length for the error if the length is
+    /// not cached.
+    ///
+    /// ```rust
+    /// use once_list2::{IndexError, OnceList};
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.try_get(1), Ok(&2));
+    /// assert_eq!(list.try_get(3), Err(IndexError { index: 3, len: 3 }));
+    /// ```
+    pub fn try_get(&self, index: usize) -> Result<&T, IndexError>
     where
-        P: FnMut(&T) -> bool,
-        F: FnMut(Box<Cons<T, T, A>, A>) -> U,
+        C: CacheMode<T, A>,
     {
-        // Any structural change through `&mut self` invalidates the cached tail slot.
-        self.cache_mode.on_structure_change();
+        self.iter().nth(index).ok_or_else(|| IndexError {
+            index,
+            len: self.len(),
+        })
+    }
 
-        let mut next_cell = &mut self.head_slot;
-        while let Some(next_ref) = next_cell.get() {
-            if pred(&next_ref.val) {
-                // Safe because we are sure the `next_cell` value is set.
-                let Some(mut next_box) = next_cell.take() else {
-                    unreachable!("remove_inner: next_cell had value but take() returned None");
-                };
+    /// Returns an iterator over the `&T` references in the list.
+    pub fn iter(&self) -> Iter<'_, T, A> {
+        Iter::new(&self.head_slot)
+    }
 
-                // reconnect the list
-                if let Some(next_next) = next_box.next.take() {
-                    let _ = next_cell.set(next_next);
-                }
+    /// Returns an iterator over the `&T` references in the list, paired with a flag which is
+    /// `true` only for the last value.
+    ///
+    /// The flag reflects the list at the time the value is yielded, i.e. a value pushed later
+    /// makes the previous last value not the last anymore.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<&str>::new();
+    /// list.extend(["a", "b", "c"]);
+    ///
+    /// let mut joined = String::new();
+    /// for (s, is_last) in list.iter_with_last() {
+    ///     joined.push_str(s);
+    ///     if !is_last {
+    ///         joined.push_str(", ");
+    ///     }
+    /// }
+    /// assert_eq!(joined, "a, b, c");
+    /// ```
+    pub fn iter_with_last(&self) -> impl Iterator<Item = (&T, bool)> + '_ {
+        let mut next_slot = &self.head_slot;
+        ::std::iter::from_fn(move || {
+            let next_box = next_slot.get()?;
+            next_slot = &next_box.next;
+            Some((&next_box.val, next_slot.get().is_none()))
+        })
+    }
 
-                self.cache_mode.on_remove_success();
-                return Some(f(next_box));
+    /// Returns an iterator over the `&T` references in the list, starting from the given index.
+    ///
+    /// This method walks `index` nodes, so it is O(index). If the index is out of range, the
+    /// returned iterator is positioned at the end of the list and yields nothing (until new
+    /// values are pushed, like other [`Iter`]s).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend(1..=5);
+    /// assert_eq!(list.iter_from(2).collect::<Vec<_>>(), vec![&3, &4, &5]);
+    /// assert_eq!(list.iter_from(10).next(), None);
+    /// ```
+    pub fn iter_from(&self, index: usize) -> Iter<'_, T, A> {
+        let mut iter = self.iter();
+        for _ in 0..index {
+            if iter.next().is_none() {
+                break;
             }
-            // Safe because we are sure the `next_cell` value is set.
-            let Some(next_box) = next_cell.get_mut() else {
-                unreachable!("remove_inner: next_cell had value but get_mut() returned None");
-            };
-            next_cell = &mut next_box.next;
         }
-        None
+        iter
     }
-}
 
-impl<T: ?Sized, A: Allocator + Clone, C> OnceListCore<T, A, C>
-where
-    C: CacheMode<T, A>,
-{
-    /// An unsized version of the [`OnceList::push`] method.
-    ///
-    /// You can push a sized value to the list. For exaple, you can push `[i32; 3]` to the list of `[i32]`.
-    #[cfg(feature = "nightly")]
-    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
-    pub fn push_unsized<U: Unsize<T>>(&self, val: U) -> &U {
-        let boxed_cons = Cons::new_boxed(val, self.alloc.clone());
-        self.push_inner(boxed_cons, |c| unsafe { &*(c as *const T as *const U) })
+    /// Returns an iterator over the `&mut T` references in the list.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, A>
+    where
+        C: CacheMode<T, A>,
+    {
+        IterMut::new(self.nodes_mut())
     }
 
-    /// An inner implementation for the `push_xxx` methods.
-    pub(crate) fn push_inner<F, U>(&self, mut new_cons: Box<Cons<T, T, A>, A>, f: F) -> &U
+    /// Returns an iterator over the `&mut T` references in the list, in the back-to-front order.
+    ///
+    /// Note that this method collects all the references into a buffer first, which is a one-time
+    /// O(n) time and memory cost.
+    pub fn iter_mut_rev(&mut self) -> IterMutRev<'_, T>
     where
-        F: FnOnce(&T) -> &U,
+        C: CacheMode<T, A>,
     {
-        let mut next_cell = self.cache_mode.tail_slot_opt().unwrap_or(&self.head_slot);
-        loop {
-            match next_cell.try_insert2(new_cons) {
-                Ok(new_cons) => {
-                    self.cache_mode.on_push_success(&new_cons.next);
-                    return f(&new_cons.val);
-                }
-                Err((cur_cons, new_cons2)) => {
-                    next_cell = &cur_cons.next;
-                    new_cons = new_cons2;
-                }
-            }
-        }
+        IterMutRev::new(self.iter_mut())
     }
-}
 
-impl<T, A: Allocator, C> OnceListCore<T, A, C>
-where
-    C: CacheMode<T, A>,
-{
-    /// Removes the front value from the list, and returns it.
+    /// Returns the mutable reference to the `n`-th value in the list, in the same manner as
+    /// [`Iterator::nth`].
     ///
-    /// This method is O(1).
-    pub fn pop_front(&mut self) -> Option<T> {
-        self.remove(|_| true)
+    /// This method is O(n).
+    pub fn nth_mut(&mut self, n: usize) -> Option<&mut T>
+    where
+        C: CacheMode<T, A>,
+    {
+        self.iter_mut().nth(n)
     }
 
-    /// Find a first value in the list matches the predicate, remove that item from the list,
-    /// and then returns that value.
-    pub fn remove<P>(&mut self, mut pred: P) -> Option<T>
+    /// Calls the fallible closure on each value in the list, stopping at the first error and
+    /// returning it.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    ///
+    /// let mut seen = Vec::new();
+    /// let res = list.try_for_each(|&v| {
+    ///     seen.push(v);
+    ///     if v == 2 { Err(v) } else { Ok(()) }
+    /// });
+    /// assert_eq!(res, Err(2));
+    /// assert_eq!(seen, vec![1, 2]);
+    /// ```
+    pub fn try_for_each<E, F>(&self, f: F) -> Result<(), E>
     where
-        P: FnMut(&T) -> bool,
+        F: FnMut(&T) -> Result<(), E>,
     {
-        self.remove_inner(&mut pred, |boxed_cons| Box::into_inner(boxed_cons).val)
+        self.iter().try_for_each(f)
     }
-}
 
-impl<T, A: Allocator + Clone, C> OnceListCore<T, A, C>
-where
-    C: CacheMode<T, A>,
-{
-    /// Appends a value to the back of the list, and returns the reference to that value.
+    /// Calls the closure on each value in the list, and returns `&self` for the method chaining.
     ///
-    /// Note that this method takes `&self`, not `&mut self`.
-    pub fn push_back(&self, val: T) -> &T {
-        let boxed_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2]);
+    ///
+    /// let mut seen = Vec::new();
+    /// list.inspect(|&v| seen.push(v)).push_back(3);
+    /// assert_eq!(seen, vec![1, 2]);
+    /// assert_eq!(list.len(), 3);
+    /// ```
+    pub fn inspect<F>(&self, f: F) -> &Self
+    where
+        F: FnMut(&T),
+    {
+        self.iter().for_each(f);
+        self
+    }
+
+    /// Returns `true` if the two lists contain the same values with the same multiplicities,
+    /// ignoring the order.
+    ///
+    /// This is useful for the lists built concurrently under the `sync` feature, whose order is
+    /// nondeterministic. The references to the values are sorted, so this method is O(n log n)
+    /// and allocates two buffers. See [`OnceListCore::eq_ignore_order_hashed`] for a hash based
+    /// version.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::from([1, 2, 2, 3]);
+    /// assert!(list.eq_ignore_order(&OnceList::from([2, 3, 2, 1])));
+    /// assert!(!list.eq_ignore_order(&OnceList::from([1, 2, 3, 3])));
+    /// ```
+    pub fn eq_ignore_order<A2, C2>(&self, other: &OnceListCore<T, A2, C2>) -> bool
+    where
+        T: Ord,
+        A2: Allocator,
+    {
+        let mut lhs = self.iter().collect::<Vec<_>>();
+        let mut rhs = other.iter().collect::<Vec<_>>();
+        if lhs.len() != rhs.len() {
+            return false;
+        }
+        lhs.sort_unstable();
+        rhs.sort_unstable();
+        lhs == rhs
+    }
+
+    /// Same as [`OnceListCore::eq_ignore_order`], but counts the values in a hash map instead of
+    /// sorting them. This method is O(n) on average.
+    pub fn eq_ignore_order_hashed<A2, C2>(&self, other: &OnceListCore<T, A2, C2>) -> bool
+    where
+        T: Hash + Eq,
+        A2: Allocator,
+    {
+        let mut counts = ::std::collections::HashMap::<&T, isize>::new();
+        for val in self.iter() {
+            *counts.entry(val).or_default() += 1;
+        }
+        for val in other.iter() {
+            *counts.entry(val).or_default() -= 1;
+        }
+        counts.values().all(|&count| count == 0)
+    }
+
+    /// Returns an iterator over the values of this list, followed by the values of the other
+    /// list which are not in this list.
+    ///
+    /// This is for using the lists as small sets: the duplicates within each list are kept as
+    /// they are. Each value of the other list is searched linearly in this list, so this method
+    /// is O(n * m). See [`OnceListCore::union_hashed`] for a hash based version.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let a = OnceList::from([1, 2, 3]);
+    /// let b = OnceList::from([4, 2, 5]);
+    /// assert_eq!(a.union(&b).collect::<Vec<_>>(), vec![&1, &2, &3, &4, &5]);
+    /// assert_eq!(a.intersection(&b).collect::<Vec<_>>(), vec![&2]);
+    /// assert_eq!(a.difference(&b).collect::<Vec<_>>(), vec![&1, &3]);
+    /// ```
+    pub fn union<'a, A2, C2>(
+        &'a self,
+        other: &'a OnceListCore<T, A2, C2>,
+    ) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: PartialEq,
+        A2: Allocator,
+    {
+        self.iter()
+            .chain(other.iter().filter(move |v| !self.iter().any(|w| w == *v)))
+    }
+
+    /// Returns an iterator over the values of this list which are also in the other list.
+    ///
+    /// This method is O(n * m). See [`OnceListCore::union`] for the details.
+    pub fn intersection<'a, A2, C2>(
+        &'a self,
+        other: &'a OnceListCore<T, A2, C2>,
+    ) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: PartialEq,
+        A2: Allocator,
+    {
+        self.iter().filter(move |v| other.iter().any(|w| w == *v))
+    }
+
+    /// Returns an iterator over the values of this list which are not in the other list.
+    ///
+    /// This method is O(n * m). See [`OnceListCore::union`] for the details.
+    pub fn difference<'a, A2, C2>(
+        &'a self,
+        other: &'a OnceListCore<T, A2, C2>,
+    ) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: PartialEq,
+        A2: Allocator,
+    {
+        self.iter().filter(move |v| !other.iter().any(|w| w == *v))
+    }
+
+    /// Same as [`OnceListCore::union`], but collects the values of this list into a hash set
+    /// first. This method is O(n + m) on average.
+    ///
+    /// The hash set is a snapshot: the values pushed to this list after the call are not taken
+    /// into account when filtering the other list's values.
+    pub fn union_hashed<'a, A2, C2>(
+        &'a self,
+        other: &'a OnceListCore<T, A2, C2>,
+    ) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: Hash + Eq,
+        A2: Allocator,
+    {
+        let seen = self.iter().collect::<::std::collections::HashSet<_>>();
+        self.iter()
+            .chain(other.iter().filter(move |v| !seen.contains(*v)))
+    }
+
+    /// Same as [`OnceListCore::intersection`], but collects the values of the other list into a
+    /// hash set first. This method is O(n + m) on average.
+    pub fn intersection_hashed<'a, A2, C2>(
+        &'a self,
+        other: &'a OnceListCore<T, A2, C2>,
+    ) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: Hash + Eq,
+        A2: Allocator,
+    {
+        let others = other.iter().collect::<::std::collections::HashSet<_>>();
+        self.iter().filter(move |v| others.contains(*v))
+    }
+
+    /// Same as [`OnceListCore::difference`], but collects the values of the other list into a
+    /// hash set first. This method is O(n + m) on average.
+    pub fn difference_hashed<'a, A2, C2>(
+        &'a self,
+        other: &'a OnceListCore<T, A2, C2>,
+    ) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: Hash + Eq,
+        A2: Allocator,
+    {
+        let others = other.iter().collect::<::std::collections::HashSet<_>>();
+        self.iter().filter(move |v| !others.contains(*v))
+    }
+
+    /// Consumes and leaks the list, returning a mutable reference to it which lives as long as
+    /// `'a`, e.g. `'static`.
+    ///
+    /// This is analogous to [`Vec::leak`]. The list itself is moved into a leaked box, and its
+    /// nodes are never freed. So the references returned by [`OnceListCore::iter`] or
+    /// [`OnceListCore::push_back`] on the leaked list live as long as `'a` too, which is handy
+    /// for registry-style globals.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list: &'static mut OnceList<&str> = OnceList::from(["a", "b"]).leak();
+    /// # let raw: *mut OnceList<&str> = list;
+    /// # let list: &'static OnceList<&str> = unsafe { &*raw };
+    /// let pushed: &'static &str = list.push("c");
+    /// let all: Vec<&'static &str> = list.iter().collect();
+    /// assert_eq!(all, vec![&"a", &"b", pushed]);
+    /// # // Free the list, so that the example passes Miri's leak check.
+    /// # drop(unsafe { Box::from_raw(raw) });
+    /// ```
+    pub fn leak<'a>(self) -> &'a mut Self
+    where
+        Self: 'a,
+    {
+        ::std::boxed::Box::leak(::std::boxed::Box::new(self))
+    }
+
+    /// Returns an allocator of this struct.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+}
+
+impl<T, A: Allocator, C> OnceListCore<T, A, C> {
+    /// Returns `true` if the list contains the given slice as a contiguous run of values.
+    ///
+    /// An empty slice is always contained. This method is O(n * m), where m is the length of `pat`.
+    pub fn contains_subsequence(&self, pat: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut start = self.iter();
+        loop {
+            let mut window = Iter::new(start.next_slot);
+            if pat.iter().all(|p| window.next() == Some(p)) {
+                return true;
+            }
+            if start.next().is_none() {
+                return false;
+            }
+        }
+    }
+
+    /// Returns `true` if the front values of the list are equal to the given slice.
+    pub fn starts_with(&self, pat: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut iter = self.iter();
+        pat.iter().all(|p| iter.next() == Some(p))
+    }
+
+    /// Returns `true` if the back values of the list are equal to the given slice.
+    ///
+    /// This method uses [`OnceListCore::len`] to align the suffix, so it is O(n) in any cache mode.
+    pub fn ends_with(&self, pat: &[T]) -> bool
+    where
+        T: PartialEq,
+        C: CacheMode<T, A>,
+    {
+        let Some(skip) = self.len().checked_sub(pat.len()) else {
+            return false;
+        };
+        self.iter().skip(skip).eq(pat.iter())
+    }
+
+    /// Returns `true` if the front values of the list are equal to the values of the other list.
+    ///
+    /// The other list can have a different allocator and cache mode. This method stops at the
+    /// first mismatch.
+    ///
+    /// ```rust
+    /// use once_list2::{OnceList, OnceListWithLen};
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// let prefix = OnceListWithLen::<i32>::new();
+    /// prefix.extend([1, 2]);
+    /// assert!(list.starts_with_other(&prefix));
+    /// assert!(!prefix.starts_with_other(&list));
+    /// ```
+    pub fn starts_with_other<A2, C2>(&self, other: &OnceListCore<T, A2, C2>) -> bool
+    where
+        T: PartialEq,
+        A2: Allocator,
+    {
+        let mut iter = self.iter();
+        other.iter().all(|p| iter.next() == Some(p))
+    }
+
+    /// Returns `true` if the back values of the list are equal to the values of the other list.
+    ///
+    /// The other list can have a different allocator and cache mode. Like
+    /// [`OnceListCore::ends_with`], this method uses the lengths of both lists to align the
+    /// suffix, so it is O(n) in any cache mode.
+    pub fn ends_with_other<A2, C2>(&self, other: &OnceListCore<T, A2, C2>) -> bool
+    where
+        T: PartialEq,
+        C: CacheMode<T, A>,
+        A2: Allocator,
+        C2: CacheMode<T, A2>,
+    {
+        let Some(skip) = self.len().checked_sub(other.len()) else {
+            return false;
+        };
+        self.iter().skip(skip).eq(other.iter())
+    }
+
+    /// Swaps the two values at the given indices in place.
+    ///
+    /// The values are exchanged between the nodes and the nodes are not relinked, so no
+    /// allocation happens. This method walks the list once, so it is O(max(a, b)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of range.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3, 4]);
+    /// list.swap(3, 1);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &4, &3, &2]);
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize)
+    where
+        C: CacheMode<T, A>,
+    {
+        let (lo, hi) = (a.min(b), a.max(b));
+        let mut iter = self.iter_mut();
+        let lo_val = iter.nth(lo);
+        let hi_val = if lo == hi {
+            None
+        } else {
+            iter.nth(hi - lo - 1)
+        };
+        match (lo_val, hi_val) {
+            (Some(lo_val), Some(hi_val)) => ::std::mem::swap(lo_val, hi_val),
+            (Some(_), None) if lo == hi => {}
+            _ => panic!(
+                "{}",
+                IndexError {
+                    index: hi,
+                    len: self.len(),
+                }
+            ),
+        }
+    }
+
+    /// Fills the list with clones of the given value, overwriting every value in place.
+    ///
+    /// This is the same as [`slice::fill`]. The list structure is not changed.
+    pub fn fill(&mut self, val: T)
+    where
+        T: Clone,
+        C: CacheMode<T, A>,
+    {
+        for v in self.iter_mut() {
+            v.clone_from(&val);
+        }
+    }
+
+    /// Fills the list with values returned by calling the closure repeatedly, overwriting every
+    /// value in place.
+    ///
+    /// This is the same as [`slice::fill_with`]. The list structure is not changed.
+    pub fn fill_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut() -> T,
+        C: CacheMode<T, A>,
+    {
+        for v in self.iter_mut() {
+            *v = f();
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Returns the head slot, to hand out mutable references into the nodes.
+    ///
+    /// This drops the cached tail slot first, because a mutable borrow of a node invalidates the
+    /// pointers into it. See [`CacheMode::on_structure_change`].
+    pub(crate) fn nodes_mut(&mut self) -> &mut NextSlot<T, A> {
+        self.cache_mode.on_structure_change();
+        &mut self.head_slot
+    }
+
+    /// Returns a new empty list with a clone of this list's allocator and the same cache mode.
+    pub(crate) fn new_empty_like(&self) -> Self
+    where
+        A: Clone,
+    {
+        self.new_empty_like_in(self.alloc.clone())
+    }
+
+    /// Returns a new empty list with the given allocator and the same cache mode.
+    pub(crate) fn new_empty_like_in(&self, alloc: A) -> Self {
+        let cache_mode = self.cache_mode.clone();
+        cache_mode.on_clear();
+        cache_mode.on_structure_change();
+        Self {
+            head_slot: NextSlot::new(),
+            alloc,
+            cache_mode,
+        }
+    }
+
+    /// Walks the whole list once, and re-establishes the caches of the current cache mode, i.e.
+    /// the tail insertion slot and/or the length. This is a no-op for [`NoCache`].
+    ///
+    /// This method is always O(n), and can be called at any time.
+    pub fn refresh_caches(&self) {
+        let mut last_next_slot = None;
+        let mut len = 0;
+        let mut next_slot = &self.head_slot;
+        while let Some(next_box) = next_slot.get() {
+            next_slot = &next_box.next;
+            last_next_slot = Some(next_slot);
+            len += 1;
+        }
+        self.cache_mode.on_refresh(last_next_slot, len);
+    }
+
+    /// Clears the list, dropping all values.
+    pub fn clear(&mut self) {
+        self.head_slot = NextSlot::new();
+        self.cache_mode.on_clear();
+        self.cache_mode.on_structure_change();
+    }
+
+    /// Takes all the values out of the list into a new list, leaving this list empty.
+    ///
+    /// This is like [`std::mem::take`], but works even if the allocator does not implement
+    /// `Default`: this list keeps a clone of its allocator and stays reusable. The nodes and the
+    /// caches are moved as they are, so this method is O(1).
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2]);
+    /// let taken = list.take();
+    ///
+    /// assert_eq!(taken.len(), 2);
+    /// assert!(list.is_empty());
+    /// list.push(3);
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn take(&mut self) -> Self
+    where
+        A: Clone,
+    {
+        let empty = self.new_empty_like();
+        ::std::mem::replace(self, empty)
+    }
+
+    /// Shortens the list, keeping the first `len` values and dropping the rest.
+    ///
+    /// This has no effect if `len` is greater than or equal to the list's current length. This
+    /// method is O(n).
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithTailLen;
+    ///
+    /// let mut list = OnceListWithTailLen::<i32>::new();
+    /// list.extend([1, 2, 3, 4]);
+    /// list.truncate(2);
+    /// list.push(5);
+    ///
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &5]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        for _ in 0..len {
+            let Some(next_box) = next_cell.get_mut() else {
+                // The list is not longer than `len`, so nothing is changed.
+                return;
+            };
+            next_cell = &mut next_box.next;
+        }
+        drop(next_cell.take());
+
+        // Only the length is kept. `next_cell` has been written through the `&mut` walk, so a
+        // pointer derived from it would be invalidated by the next shared read of the node. The
+        // next push finds the tail slot again.
+        self.cache_mode.on_refresh(None, len);
+    }
+
+    /// Removes consecutive repeated values in the list.
+    ///
+    /// This is the same as [`Vec::dedup`].
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 1, 2, 3, 3, 3, 1]);
+    /// list.dedup();
+    ///
+    /// assert_eq!(list.len(), 4);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &1]);
+    /// ```
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes all but the first of consecutive values in the list that resolve to the same key.
+    ///
+    /// This is the same as [`Vec::dedup_by_key`].
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive values in the list satisfying the given equality
+    /// relation.
+    ///
+    /// This is the same as [`Vec::dedup_by`]: the closure is called with the later value first,
+    /// and the later value is removed if it returns `true`.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.cache_mode.on_structure_change();
+
+        let Some(head_box) = self.head_slot.get_mut() else {
+            return;
+        };
+        let mut cur_cons: &mut Cons<T, T, A> = head_box;
+        loop {
+            let is_dup = match cur_cons.next.get_mut() {
+                Some(next_box) => same_bucket(&mut next_box.val, &mut cur_cons.val),
+                None => return,
+            };
+            if is_dup {
+                // Safe because we are sure the `next` value is set.
+                let Some(mut removed) = cur_cons.next.take() else {
+                    unreachable!("dedup_by: next had value but take() returned None");
+                };
+                // reconnect the list
+                if let Some(next_next) = removed.next.take() {
+                    let _ = cur_cons.next.set(next_next);
+                }
+                self.cache_mode.on_remove_success();
+            } else {
+                // Safe because we are sure the `next` value is set.
+                let Some(next_box) = cur_cons.next.get_mut() else {
+                    unreachable!("dedup_by: next had value but get_mut() returned None");
+                };
+                cur_cons = next_box;
+            }
+        }
+    }
+
+    /// Retains only the values specified by the predicate, in a single walk over the list.
+    ///
+    /// This is the same as [`Vec::retain`].
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend(1..=6);
+    /// list.retain(|v| v % 2 == 0);
+    ///
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&2, &4, &6]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|v| f(v));
+    }
+
+    /// Retains only the values specified by the predicate, passing a mutable reference to it.
+    ///
+    /// This is the same as [`Vec::retain_mut`].
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        loop {
+            let keep = match next_cell.get_mut() {
+                Some(next_box) => f(&mut next_box.val),
+                None => return,
+            };
+            if keep {
+                // Safe because we are sure the `next_cell` value is set.
+                let Some(next_box) = next_cell.get_mut() else {
+                    unreachable!("retain_mut: next_cell had value but get_mut() returned None");
+                };
+                next_cell = &mut next_box.next;
+            } else {
+                // Safe because we are sure the `next_cell` value is set.
+                let Some(mut removed) = next_cell.take() else {
+                    unreachable!("retain_mut: next_cell had value but take() returned None");
+                };
+                // reconnect the list
+                if let Some(next_next) = removed.next.take() {
+                    let _ = next_cell.set(next_next);
+                }
+                self.cache_mode.on_remove_success();
+            }
+        }
+    }
+
+    /// Reverses the order of the values in the list, by relinking the nodes.
+    ///
+    /// The values are not moved in memory, and no allocation happens. This method is O(n).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// list.reverse();
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+    /// ```
+    pub fn reverse(&mut self) {
+        self.cache_mode.on_structure_change();
+
+        let mut rest = ::std::mem::replace(&mut self.head_slot, NextSlot::new());
+        while let Some(node) = rest.unlink_first(|_| true) {
+            if let Some(reversed) = self.head_slot.take() {
+                let _ = node.next.set(reversed);
+            }
+            let _ = self.head_slot.set(node);
+        }
+    }
+
+    /// Merges the two sorted lists into one sorted list.
+    ///
+    /// The merge is stable: if the values are equal, the values from this list come first. The
+    /// nodes are only relinked, so no allocation happens. Note that the nodes from the other
+    /// list stay allocated by the other list's allocator instance. This method is O(n + m).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 3, 5]);
+    /// let other = OnceList::<i32>::new();
+    /// other.extend([2, 3, 4]);
+    /// let merged = list.merge(other);
+    ///
+    /// assert_eq!(merged.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &3, &4, &5]);
+    /// ```
+    pub fn merge(self, other: Self) -> Self
+    where
+        T: Ord,
+    {
+        self.merge_by(other, T::cmp)
+    }
+
+    /// Merges the two lists sorted by the comparator function into one sorted list.
+    ///
+    /// See [`OnceListCore::merge`] for the details.
+    pub fn merge_by<F>(mut self, mut other: Self, mut compare: F) -> Self
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut rhs = ::std::mem::replace(&mut other.head_slot, NextSlot::new());
+        self.head_slot.merge_by(&mut rhs, &mut compare);
+        self.refresh_caches();
+        self
+    }
+
+    /// Sorts the list, in the same manner as [`slice::sort`].
+    ///
+    /// The sort is stable, and done by a bottom-up merge sort which only relinks the nodes.
+    /// So the values are not moved in memory, and no allocation happens. This method is
+    /// O(n log n).
+    ///
+    /// If the comparison panics, all the values are kept in the list in an unspecified order,
+    /// and the caches stay valid.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([3, 1, 2]);
+    /// list.sort();
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    /// Sorts the list with the key extraction function, in the same manner as
+    /// [`slice::sort_by_key`].
+    ///
+    /// See [`OnceListCore::sort`] for the details.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Sorts the list with the comparator function, in the same manner as [`slice::sort_by`].
+    ///
+    /// See [`OnceListCore::sort`] for the details.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        /// Links all the nodes back into the list and re-primes the caches when dropped, so that
+        /// no node is lost even if `compare` panics.
+        struct Guard<'s, T: ?Sized, A: Allocator, C: CacheMode<T, A>> {
+            list: &'s mut OnceListCore<T, A, C>,
+            unsorted: NextSlot<T, A>,
+            carry: NextSlot<T, A>,
+            runs: [NextSlot<T, A>; usize::BITS as usize],
+        }
+
+        impl<T: ?Sized, A: Allocator, C: CacheMode<T, A>> Drop for Guard<'_, T, A, C> {
+            fn drop(&mut self) {
+                // The higher runs hold the earlier nodes. After a successful sort, only `carry`
+                // has the nodes.
+                for run in self.runs.iter_mut().rev() {
+                    let run = ::std::mem::replace(run, NextSlot::new());
+                    self.list.head_slot.append(run);
+                }
+                let carry = ::std::mem::replace(&mut self.carry, NextSlot::new());
+                self.list.head_slot.append(carry);
+                let unsorted = ::std::mem::replace(&mut self.unsorted, NextSlot::new());
+                self.list.head_slot.append(unsorted);
+                self.list.refresh_caches();
+            }
+        }
+
+        self.cache_mode.on_structure_change();
+
+        let unsorted = ::std::mem::replace(&mut self.head_slot, NextSlot::new());
+        // `runs[i]` is either empty or a sorted run of `2^i` nodes. The higher runs hold the
+        // earlier nodes, so merging them in front of the lower runs keeps the sort stable.
+        let mut guard = Guard {
+            list: self,
+            unsorted,
+            carry: NextSlot::new(),
+            runs: ::std::array::from_fn(|_| NextSlot::new()),
+        };
+        while let Some(node) = guard.unsorted.unlink_first(|_| true) {
+            let _ = guard.carry.set(node);
+            for run in guard.runs.iter_mut() {
+                if run.get().is_none() {
+                    *run = ::std::mem::replace(&mut guard.carry, NextSlot::new());
+                    break;
+                }
+                run.merge_by(&mut guard.carry, &mut compare);
+                guard.carry = ::std::mem::replace(run, NextSlot::new());
+            }
+        }
+
+        for run in guard.runs.iter_mut() {
+            run.merge_by(&mut guard.carry, &mut compare);
+            guard.carry = ::std::mem::replace(run, NextSlot::new());
+        }
+        // The guard moves the sorted chain in `carry` into the list, and refreshes the caches.
+    }
+
+    /// Calls the closure on each adjacent pair of values `(prev, cur)` in the list, with mutable
+    /// references to both of them.
+    ///
+    /// This is a closure based method instead of an iterator, because an iterator's items are
+    /// allowed to coexist, while each value (except the first and the last) appears in two pairs.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([3, 1, 2]);
+    ///
+    /// // A single bubble sort pass.
+    /// list.for_each_adjacent_pair_mut(|prev, cur| {
+    ///     if prev > cur {
+    ///         std::mem::swap(prev, cur);
+    ///     }
+    /// });
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    pub fn for_each_adjacent_pair_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T, &mut T),
+    {
+        let Some(head_box) = self.nodes_mut().get_mut() else {
+            return;
+        };
+        let mut cur_cons: &mut Cons<T, T, A> = head_box;
+        while let Some(next_box) = cur_cons.next.get_mut() {
+            f(&mut cur_cons.val, &mut next_box.val);
+            cur_cons = next_box;
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Removes the first value in the list that matches the predicate, and returns the value as a boxed value.
+    ///
+    /// This method supports the unsized value type `T` as well.
+    ///
+    /// Note that even though this method returns a boxed value, the box is something re-allcoated.
+    /// So this method might not be efficient as you expect.
+    pub fn remove_into_box<P>(&mut self, pred: P) -> Option<Box<T, A>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.remove_inner(pred, Cons::box_into_inner_box)
+    }
+
+    /// Removes the front value from the list, and returns the value as a boxed value.
+    ///
+    /// This method supports the unsized value type `T` as well. Like [`OnceListCore::remove_into_box`],
+    /// the returned box is re-allocated.
+    ///
+    /// This method is O(1).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<str>::new();
+    /// list.push_str("hello");
+    /// list.push_str("world");
+    ///
+    /// assert_eq!(list.pop_front_boxed().as_deref(), Some("hello"));
+    /// assert_eq!(list.pop_front_boxed().as_deref(), Some("world"));
+    /// assert_eq!(list.pop_front_boxed(), None);
+    /// ```
+    pub fn pop_front_boxed(&mut self) -> Option<Box<T, A>> {
+        self.remove_inner(|_| true, Cons::box_into_inner_box)
+    }
+
+    /// Removes the first value in the list that matches the predicate, and returns the value.
+    ///
+    /// The predicate function `pred` should return `Some(&U)` if the value is found,
+    /// and the returned reference `&U` must be the same address as the value given in the `pred`.
+    ///
+    /// # Safety
+    /// This method is unsafe because it requires the predicate to return a reference to the same address as the value.
+    #[cfg(feature = "nightly")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
+    pub unsafe fn remove_unsized_as<P, U>(&mut self, mut pred: P) -> Option<U>
+    where
+        P: FnMut(&T) -> Option<&U>,
+    {
+        use ::std::cell::Cell;
+
+        let found_sized_ptr: Cell<Option<*const U>> = Cell::new(None);
+        self.remove_inner(
+            |val| {
+                if let Some(val) = pred(val) {
+                    found_sized_ptr.set(Some(val as *const U));
+                    true
+                } else {
+                    false
+                }
+            },
+            |boxed_cons| -> U {
+                // Double check the ptr returned by the `pred` is the same as the value's address.
+                debug_assert_eq!(
+                    found_sized_ptr.get(),
+                    Some(::std::ptr::addr_of!(boxed_cons.val).cast::<U>())
+                );
+                // Safe because the caller guarantees the value is `U` at the same address.
+                unsafe { Cons::box_into_inner_as::<U>(boxed_cons) }
+            },
+        )
+    }
+
+    /// An inner implementeation for `remove_xxx` methods.
+    pub(crate) fn remove_inner<P, F, U>(&mut self, pred: P, f: F) -> Option<U>
+    where
+        P: FnMut(&T) -> bool,
+        F: FnOnce(Box<Cons<T, T, A>, A>) -> U,
+    {
+        // Any structural change through `&mut self` invalidates the cached tail slot.
+        self.cache_mode.on_structure_change();
+
+        let removed = self.head_slot.unlink_first(pred)?;
+        self.cache_mode.on_remove_success();
+        Some(f(removed))
+    }
+
+    /// Moves the first value in the list that matches the predicate to the front of the list.
+    ///
+    /// The node is relinked, so the value itself does not move in memory. Returns `true` if the
+    /// value has moved, i.e. `false` if no value matches or the front value already matches.
+    pub fn move_to_front<P>(&mut self, mut pred: P) -> bool
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.cache_mode.on_structure_change();
+
+        let Some(head_box) = self.head_slot.get_mut() else {
+            return false;
+        };
+        if pred(&head_box.val) {
+            return false;
+        }
+        let Some(moved) = head_box.next.unlink_first(pred) else {
+            return false;
+        };
+        if let Some(old_head) = self.head_slot.take() {
+            let _ = moved.next.set(old_head);
+        }
+        let _ = self.head_slot.set(moved);
+        true
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// An unsized version of the [`OnceList::push`] method.
+    ///
+    /// You can push a sized value to the list. For exaple, you can push `[i32; 3]` to the list of `[i32]`.
+    #[cfg(feature = "nightly")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
+    pub fn push_unsized<U: Unsize<T>>(&self, val: U) -> &U {
+        let boxed_cons = Cons::new_boxed(val, self.alloc.clone());
+        self.push_inner(boxed_cons, |c| unsafe { &*(c as *const T as *const U) })
+    }
+
+    /// An unsized version of the [`OnceList::extend`] method.
+    ///
+    /// Each value is coerced into `T`, and appended in order. Like `extend`, this method finds
+    /// the tail insertion point only once.
+    #[cfg(feature = "nightly")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
+    pub fn extend_unsized<U, I>(&self, iter: I)
+    where
+        U: Unsize<T>,
+        I: IntoIterator<Item = U>,
+    {
+        self.extend_inner(
+            iter.into_iter()
+                .map(|val| Cons::new_boxed(val, self.alloc.clone())),
+        );
+    }
+
+    /// Removes all the values matching the predicate from this list, and appends them in order
+    /// to the other list.
+    ///
+    /// The nodes themselves are moved, so the values are neither reallocated nor moved in memory.
+    /// Note that the moved nodes stay allocated by this list's allocator instance.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend(1..=6);
+    /// let evens = OnceList::<i32>::new();
+    /// list.drain_filter_into(|v| v % 2 == 0, &evens);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3, &5]);
+    /// assert_eq!(evens.iter().collect::<Vec<_>>(), vec![&2, &4, &6]);
+    /// ```
+    pub fn drain_filter_into<P, C2>(&mut self, pred: P, dest: &OnceListCore<T, A, C2>)
+    where
+        P: FnMut(&T) -> bool,
+        C2: CacheMode<T, A>,
+    {
+        self.cache_mode.on_structure_change();
+
+        let cache_mode = &self.cache_mode;
+        dest.extend_inner(
+            self.head_slot
+                .unlink_where(pred)
+                .inspect(|_| cache_mode.on_remove_success()),
+        );
+    }
+
+    /// Consumes the list, and splits it into two lists: the values matching the predicate, and
+    /// the others. Both lists keep the order of the values.
+    ///
+    /// The nodes are only relinked, so no allocation happens. The lists have clones of this
+    /// list's allocator and the same cache mode. This method is O(n).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend(1..=6);
+    /// let (evens, odds) = list.partition(|v| v % 2 == 0);
+    ///
+    /// assert_eq!(evens.iter().collect::<Vec<_>>(), vec![&2, &4, &6]);
+    /// assert_eq!(odds.iter().collect::<Vec<_>>(), vec![&1, &3, &5]);
+    /// ```
+    pub fn partition<P>(mut self, pred: P) -> (Self, Self)
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let matched = self.new_empty_like();
+        self.drain_filter_into(pred, &matched);
+        (matched, self)
+    }
+
+    /// Splits the list into two at the given index, and returns the values from the index
+    /// (inclusive) as a new list. This list keeps the values before the index.
+    ///
+    /// The nodes are not reallocated, only the link at the index is cut. The new list has a clone
+    /// of this list's allocator and the same cache mode. This method is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithTailLen;
+    ///
+    /// let mut list = OnceListWithTailLen::<i32>::new();
+    /// list.extend([1, 2, 3, 4, 5]);
+    /// let rest = list.split_off(2);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&3, &4, &5]);
+    /// assert_eq!(rest.len(), 3);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let other = self.new_empty_like();
+
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        for index in 0..at {
+            let Some(next_box) = next_cell.get_mut() else {
+                panic!("split_off: `at` (is {at}) is out of range, the len is {index}");
+            };
+            next_cell = &mut next_box.next;
+        }
+        if let Some(rest) = next_cell.take() {
+            let _ = other.head_slot.set(rest);
+        }
+
+        // Only the length is kept. `next_cell` has been written through the `&mut` walk, so a
+        // pointer derived from it would be invalidated by the next shared read of the node. The
+        // next push finds the tail slot again.
+        self.cache_mode.on_refresh(None, at);
+        other.refresh_caches();
+        other
+    }
+
+    /// Moves all the values of the other list to the back of this list, leaving the other list
+    /// empty.
+    ///
+    /// The nodes are relinked instead of reallocated. Finding the other list's last node is O(1)
+    /// if its tail is cached, and finding this list's tail insertion point is O(1) if its tail
+    /// is cached. Note that the moved nodes stay allocated by the other list's allocator instance.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithTailLen;
+    ///
+    /// let mut list = OnceListWithTailLen::<i32>::new();
+    /// list.extend([1, 2]);
+    /// let mut other = OnceListWithTailLen::<i32>::new();
+    /// other.extend([3, 4]);
+    /// list.append(&mut other);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    /// assert_eq!(list.len(), 4);
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        let count = other.len();
+        let mut tail = other
+            .cache_mode
+            .tail_slot_opt(&other.head_slot)
+            .unwrap_or(&other.head_slot);
+        while let Some(next_box) = tail.get() {
+            tail = &next_box.next;
+        }
+        let tail = NonNull::from(tail);
+
+        let Some(first) = other.head_slot.take() else {
+            return;
+        };
+        other.cache_mode.on_clear();
+        other.cache_mode.on_structure_change();
+
+        self.attach_chain(Chain { first, tail, count });
+    }
+
+    /// Pushes a boxed value to the list, and returns the reference to that value.
+    ///
+    /// This method supports the unsized value type `T` as well, e.g. `Box<[u8]>` or `Box<dyn Trait>`,
+    /// even on the stable compiler.
+    ///
+    /// Note that the value is moved (memcpy) into a newly allocated node, and the given box's
+    /// memory is freed.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    /// use allocator_api2::boxed::Box;
+    ///
+    /// let list = OnceList::<[u64]>::new();
+    /// list.push_boxed(Box::from(&[1, 2, 3][..]));
+    /// list.push_boxed(Box::from(&[][..]));
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&[1, 2, 3][..], &[]]);
+    /// ```
+    pub fn push_boxed(&self, val: Box<T, A>) -> &T {
+        let boxed_cons = Cons::new_boxed_from_box(val, A::clone(&self.alloc));
+        self.push_inner(boxed_cons, |c| c)
+    }
+
+    /// An inner implementation for the `push_xxx` methods.
+    pub(crate) fn push_inner<F, U: ?Sized>(&self, new_cons: Box<Cons<T, T, A>, A>, f: F) -> &U
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        f(&self.push_cons(new_cons).val)
+    }
+
+    /// Appends the boxed cons to the list, and returns the reference to the inserted cons.
+    pub(crate) fn push_cons(&self, new_cons: Box<Cons<T, T, A>, A>) -> &Cons<T, T, A> {
+        let next_cell = self
+            .cache_mode
+            .tail_slot_opt(&self.head_slot)
+            .unwrap_or(&self.head_slot);
+        self.push_cons_from(next_cell, new_cons)
+    }
+
+    /// Appends the boxed cons to the first empty slot at or after the given slot, and returns
+    /// the reference to the inserted cons.
+    fn push_cons_from<'s>(
+        &'s self,
+        mut next_cell: &'s NextSlot<T, A>,
+        mut new_cons: Box<Cons<T, T, A>, A>,
+    ) -> &'s Cons<T, T, A> {
+        loop {
+            match next_cell.try_insert2(new_cons) {
+                Ok(new_cons) => {
+                    self.cache_mode.on_push_success(&new_cons.next);
+                    return new_cons;
+                }
+                Err((cur_cons, new_cons2)) => {
+                    next_cell = &cur_cons.next;
+                    new_cons = new_cons2;
+                }
+            }
+        }
+    }
+
+    /// An inner implementation for the `extend_xxx` methods.
+    ///
+    /// Without the `sync` feature, each node is appended as soon as the iterator yields it,
+    /// starting from the slot where the previous node was inserted. So the values are visible
+    /// while the iterator is running, and the values yielded before a panic stay in the list.
+    ///
+    /// With the `sync` feature, the new nodes are linked into a local chain first, without any
+    /// synchronization, and then the whole chain is attached to the list's tail at once. So the
+    /// values are not visible until the iterator is exhausted, and they are kept contiguous even
+    /// under contention.
+    pub(crate) fn extend_inner<I>(&self, new_conses: I)
+    where
+        I: IntoIterator<Item = Box<Cons<T, T, A>, A>>,
+    {
+        #[cfg(not(feature = "sync"))]
+        {
+            let mut next_cell = self
+                .cache_mode
+                .tail_slot_opt(&self.head_slot)
+                .unwrap_or(&self.head_slot);
+            for new_cons in new_conses {
+                next_cell = &self.push_cons_from(next_cell, new_cons).next;
+            }
+        }
+        #[cfg(feature = "sync")]
+        if let Some(chain) = Chain::link(new_conses) {
+            self.attach_chain(chain);
+        }
+    }
+
+    /// Attaches the chain to the list's tail.
+    fn attach_chain(&self, mut chain: Chain<T, A>) {
+        // Prefer the cached tail insertion slot when available, otherwise fall back to the head.
+        //
+        // IMPORTANT: Retry on contention so that this method never drops values under `sync`
+        // (OnceLock) mode.
+        let mut next_cell = self
+            .cache_mode
+            .tail_slot_opt(&self.head_slot)
+            .unwrap_or(&self.head_slot);
+        while let Err((cur_cons, chain2)) = self.try_attach_chain(next_cell, chain) {
+            next_cell = &cur_cons.next;
+            chain = chain2;
+        }
+    }
+
+    /// Tries to attach the chain to the given slot. Returns the existing node and the chain
+    /// back if the slot is already occupied.
+    #[allow(clippy::type_complexity)]
+    fn try_attach_chain<'s>(
+        &'s self,
+        next_cell: &'s NextSlot<T, A>,
+        chain: Chain<T, A>,
+    ) -> Result<(), (&'s Box<Cons<T, T, A>, A>, Chain<T, A>)> {
+        let Chain { first, tail, count } = chain;
+        match next_cell.try_insert2(first) {
+            Ok(inserted) => {
+                // If the chain has only one node, it has been moved so use the fresh reference.
+                let tail = if count == 1 {
+                    &inserted.next
+                } else {
+                    unsafe { tail.as_ref() }
+                };
+                self.cache_mode.on_extend_success(tail, count);
+                Ok(())
+            }
+            Err((cur_cons, first)) => Err((cur_cons, Chain { first, tail, count })),
+        }
+    }
+}
+
+/// A chain of nodes linked locally, before being attached to a list.
+struct Chain<T: ?Sized, A: Allocator> {
+    first: Box<Cons<T, T, A>, A>,
+    /// The last node's `next` slot. It lives in the heap, so it stays valid after `first` is
+    /// moved into a list.
+    tail: NonNull<NextSlot<T, A>>,
+    count: usize,
+}
+
+impl<T: ?Sized, A: Allocator> Chain<T, A> {
+    /// Links the nodes into a chain without any synchronization. Returns `None` if there is no
+    /// node.
+    fn link<I>(new_conses: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Box<Cons<T, T, A>, A>>,
+    {
+        let mut head = NextSlot::new();
+        let mut tail: &NextSlot<T, A> = &head;
+        let mut count = 0;
+        for new_cons in new_conses {
+            let _ = tail.set(new_cons);
+            // Safe because we have just set the `tail` value.
+            let Some(inserted) = tail.get() else {
+                unreachable!("Chain::link: tail was just set but get() returned None");
+            };
+            tail = &inserted.next;
+            count += 1;
+        }
+        let tail = NonNull::from(tail);
+        let first = head.take()?;
+        Some(Self { first, tail, count })
+    }
+}
+
+impl<T, A: Allocator, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Clones the values into a new `Vec`, keeping the order.
+    ///
+    /// The `Vec` is pre-sized if the length is cached.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.to_vec(), vec![1, 2, 3]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut vec = Vec::with_capacity(self.cache_mode.cached_len().unwrap_or(0));
+        vec.extend(self.iter().cloned());
+        vec
+    }
+
+    /// Moves the values into a new `Vec`, keeping the order.
+    ///
+    /// The `Vec` is pre-sized if the length is cached.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<String>::new();
+    /// list.extend(["a".to_string(), "b".to_string()]);
+    /// assert_eq!(list.into_vec(), vec!["a", "b"]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.cache_mode.cached_len().unwrap_or(0));
+        vec.extend(self);
+        vec
+    }
+
+    /// Removes the front value from the list, and returns it.
+    ///
+    /// This method is O(1).
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove(|_| true)
+    }
+
+    /// Finds the first value in the list matching the predicate, replaces it with the given value
+    /// in place, and returns the old value.
+    ///
+    /// The node is reused, so the order of the list is kept and no allocation happens. Returns
+    /// `None` (dropping the given value) if no value matches.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.replace(|&v| v == 2, 20), Some(2));
+    /// assert_eq!(list.replace(|&v| v == 4, 40), None);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &20, &3]);
+    /// ```
+    pub fn replace<P>(&mut self, mut pred: P, val: T) -> Option<T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter_mut()
+            .find(|v| pred(v))
+            .map(|old| ::std::mem::replace(old, val))
+    }
+
+    /// Removes the back value from the list, and returns it.
+    ///
+    /// This method is O(n).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2]);
+    /// assert_eq!(list.pop_back(), Some(2));
+    /// assert_eq!(list.pop_back(), Some(1));
+    /// assert_eq!(list.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        loop {
+            let is_last = next_cell.get()?.next.get().is_none();
+            if is_last {
+                break;
+            }
+            // Safe because we are sure the `next_cell` value is set.
+            let Some(next_box) = next_cell.get_mut() else {
+                unreachable!("pop_back: next_cell had value but get_mut() returned None");
+            };
+            next_cell = &mut next_box.next;
+        }
+        let removed = next_cell.take()?;
+        self.cache_mode.on_remove_success();
+        Some(Box::into_inner(removed).val)
+    }
+
+    /// Removes all the values matching the predicate from the list in a single pass, and returns
+    /// them in order.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3, 4]);
+    /// assert_eq!(list.remove_all(|v| v % 2 == 0), vec![2, 4]);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+    /// ```
+    pub fn remove_all<P>(&mut self, pred: P) -> Vec<T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.extract_if(pred).collect()
+    }
+
+    /// Removes the value at the given index from the list, and returns it. Returns `None` if the
+    /// index is out of range.
+    ///
+    /// This method is O(index).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.remove_at(1), Some(2));
+    /// assert_eq!(list.remove_at(2), None);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+    /// ```
+    pub fn remove_at(&mut self, index: usize) -> Option<T> {
+        let mut i = 0;
+        self.remove(|_| {
+            let is_target = i == index;
+            i += 1;
+            is_target
+        })
+    }
+
+    /// Find a first value in the list matches the predicate, remove that item from the list,
+    /// and then returns that value.
+    pub fn remove<P>(&mut self, mut pred: P) -> Option<T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.remove_inner(&mut pred, |boxed_cons| Box::into_inner(boxed_cons).val)
+    }
+
+    /// Removes the value at the given address from the list, and returns it.
+    ///
+    /// This is an identity based removal, for the values which are not `PartialEq` or have
+    /// duplicates. The address is typically taken from the reference returned by
+    /// [`OnceListCore::push_back`]: the reference itself cannot be passed here because it
+    /// borrows the list, but the pointer can. The pointer is only compared, never dereferenced.
+    /// See also [`crate::raw::NodeId`] for a typed identity.
+    ///
+    /// This method is O(n). Returns `None` if no value in the list is at the address.
+    ///
+    /// Once the value is removed (or the list is cleared or dropped), its memory can be reused
+    /// by a newly pushed value, and then the stale pointer would identify that new value, and
+    /// this method would remove an unrelated element. So make sure to discard the pointers of
+    /// the removed values. Unlike this method, [`crate::raw::NodeId`] detects such a stale id in
+    /// debug builds.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.push(1);
+    /// let second = std::ptr::from_ref(list.push(1));
+    /// list.push(1);
+    ///
+    /// assert_eq!(list.remove_by_ptr(second), Some(1));
+    /// assert_eq!(list.remove_by_ptr(second), None);
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    pub fn remove_by_ptr(&mut self, ptr: *const T) -> Option<T> {
+        self.remove(|val| ::std::ptr::eq(val, ptr))
+    }
+
+    /// Moves all the values into a new list which allocates its nodes with the given allocator,
+    /// keeping the order and the cache mode.
+    ///
+    /// Each value is moved into a newly allocated node, and the old nodes are freed.
+    pub fn relocate_in<B>(self, alloc: B) -> OnceListCore<T, B, C::Rebound>
+    where
+        B: Allocator + Clone,
+        C: RebindAlloc<T, B>,
+    {
+        let list = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc,
+            cache_mode: C::Rebound::default(),
+        };
+        list.extend(self);
+        list
+    }
+
+    /// Consumes the list, and returns a new list of the values mapped by the closure, keeping the
+    /// order, the allocator and the cache mode.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// let list = list.map(|v| v.to_string());
+    ///
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec!["1", "2", "3"]);
+    /// ```
+    pub fn map<U, F>(self, f: F) -> OnceListCore<U, A, C::Rebound>
+    where
+        A: Clone,
+        C: RebindAlloc<U, A>,
+        F: FnMut(T) -> U,
+    {
+        let list = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(&self.alloc),
+            cache_mode: C::Rebound::default(),
+        };
+        list.extend(self.into_iter().map(f));
+        list
+    }
+
+    /// Consumes the list, and returns a new list of the values for which the closure returns
+    /// `Some`, keeping the order, the allocator and the cache mode.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<&str>::new();
+    /// list.extend(["1", "x", "3"]);
+    /// let list = list.filter_map(|s| s.parse::<i32>().ok());
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3]);
+    /// ```
+    pub fn filter_map<U, F>(self, f: F) -> OnceListCore<U, A, C::Rebound>
+    where
+        A: Clone,
+        C: RebindAlloc<U, A>,
+        F: FnMut(T) -> Option<U>,
+    {
+        let list = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(&self.alloc),
+            cache_mode: C::Rebound::default(),
+        };
+        list.extend(self.into_iter().filter_map(f));
+        list
+    }
+
+    /// Consumes the two lists, and returns a new list of the values combined element-wise by the
+    /// closure, keeping this list's allocator and cache mode.
+    ///
+    /// The result is as long as the shorter list, and the rest of the longer list is dropped.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// let other = OnceList::<&str>::new();
+    /// other.extend(["a", "b"]);
+    /// let list = list.zip_with(other, |n, s| format!("{s}{n}"));
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec!["a1", "b2"]);
+    /// ```
+    pub fn zip_with<U, A2, C2, V, F>(
+        self,
+        other: OnceListCore<U, A2, C2>,
+        mut f: F,
+    ) -> OnceListCore<V, A, C::Rebound>
+    where
+        A: Clone,
+        A2: Allocator,
+        C: RebindAlloc<V, A>,
+        C2: CacheMode<U, A2>,
+        F: FnMut(T, U) -> V,
+    {
+        let list = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(&self.alloc),
+            cache_mode: C::Rebound::default(),
+        };
+        list.extend(self.into_iter().zip(other).map(|(t, u)| f(t, u)));
+        list
+    }
+
+    /// Returns an iterator which removes the values from the front of the list in batches of
+    /// up to `n` values, until the list becomes empty.
+    ///
+    /// Only one batch is held in memory at a time, so this is a memory-bounded way to consume
+    /// a large list. If the iterator is dropped early, the remaining values stay in the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3, 4, 5]);
+    /// let batches = list.drain_batches(2).collect::<Vec<_>>();
+    /// assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    /// assert!(list.is_empty());
+    /// ```
+    pub fn drain_batches(&mut self, n: usize) -> impl Iterator<Item = Vec<T>> + '_ {
+        assert!(n != 0, "drain_batches: batch size must be non-zero");
+        ::std::iter::from_fn(move || {
+            let batch = ::std::iter::from_fn(|| self.pop_front())
+                .take(n)
+                .collect::<Vec<_>>();
+            (!batch.is_empty()).then_some(batch)
+        })
+    }
+
+    /// Removes all the values from the list, and returns an iterator which yields them in order.
+    ///
+    /// The list becomes empty immediately, keeping its allocator, so it can be reused. The values
+    /// not consumed by the iterator are dropped together with it.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithTailLen;
+    ///
+    /// let mut list = OnceListWithTailLen::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.drain().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert!(list.is_empty());
+    ///
+    /// list.push(4);
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        let mut next_slot = NextSlot::new();
+        if let Some(head_box) = self.head_slot.take() {
+            let _ = next_slot.set(head_box);
+        }
+        self.cache_mode.on_clear();
+        self.cache_mode.on_structure_change();
+
+        ::std::iter::from_fn(move || {
+            let next_cons = Box::into_inner(next_slot.take()?);
+            next_slot = next_cons.next;
+            Some(next_cons.val)
+        })
+    }
+
+    /// Returns an iterator which removes the values from the front of the list while they match
+    /// the predicate, and yields them in order.
+    ///
+    /// The iterator stops at the first value not matching the predicate, which stays in the list
+    /// together with the rest of the values. If the iterator is dropped early, the values not
+    /// visited yet stay in the list as well.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2, 5, 3, 6]);
+    /// let expired = list.drain_while(|&t| t < 4).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(expired, vec![1, 2]);
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&5, &3, &6]);
+    /// ```
+    pub fn drain_while<'s, P>(&'s mut self, mut pred: P) -> impl Iterator<Item = T> + 's
+    where
+        P: FnMut(&T) -> bool + 's,
+    {
+        ::std::iter::from_fn(move || {
+            if pred(self.front()?) {
+                self.pop_front()
+            } else {
+                None
+            }
+        })
+        .fuse()
+    }
+
+    /// Returns an iterator which lazily removes the values matching the predicate, and yields
+    /// them in order.
+    ///
+    /// This is similar to [`Vec::extract_if`], except that the predicate takes a shared
+    /// reference like [`OnceListCore::remove`]. If the iterator is dropped early, the values not
+    /// visited yet stay in the list.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let mut list = OnceListWithLen::<i32>::new();
+    /// list.extend(1..=6);
+    /// let evens = list.extract_if(|v| v % 2 == 0).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(evens, vec![2, 4, 6]);
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &3, &5]);
+    /// ```
+    pub fn extract_if<'s, P>(&'s mut self, pred: P) -> impl Iterator<Item = T> + 's
+    where
+        P: FnMut(&T) -> bool + 's,
+    {
+        self.cache_mode.on_structure_change();
+
+        let cache_mode = &self.cache_mode;
+        self.head_slot.unlink_where(pred).map(move |boxed_cons| {
+            cache_mode.on_remove_success();
+            Box::into_inner(boxed_cons).val
+        })
+    }
+}
+
+impl<T, A: Allocator + Clone, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Appends a value to the back of the list, and returns the reference to that value.
+    ///
+    /// Note that this method takes `&self`, not `&mut self`.
+    pub fn push_back(&self, val: T) -> &T {
+        let boxed_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
         self.push_inner(boxed_cons, |c| c)
     }
 
     /// Appends a value to the list, and returns the reference to that value.
     ///
-    /// Note that this method takes `&self`, not `&mut self`.
-    pub fn push(&self, val: T) -> &T {
-        self.push_back(val)
+    /// Note that this method takes `&self`, not `&mut self`.
+    pub fn push(&self, val: T) -> &T {
+        self.push_back(val)
+    }
+
+    /// A fallible version of [`OnceListCore::push_back`], which returns an error instead of
+    /// aborting when the node allocation fails.
+    ///
+    /// On failure, the value is dropped and the list is not changed.
+    pub fn try_push(&self, val: T) -> Result<&T, AllocError> {
+        let boxed_cons = Box::try_new_in(Cons::new(val), A::clone(&self.alloc))?;
+        Ok(self.push_inner(boxed_cons, |c| c))
+    }
+
+    /// An almost same method with the [`std::iter::Extend::extend`],
+    /// though this method takes `&self` instead of `&mut self`.
+    ///
+    /// Under the `sync` feature, the values are linked together first and then appended at once,
+    /// so they stay contiguous even if other threads push concurrently. In that case, the values
+    /// become visible only after the iterator is exhausted.
+    ///
+    /// [`std::iter::Extend::extend`]: https://doc.rust-lang.org/std/iter/trait.Extend.html#tymethod.extend
+    pub fn extend<U: IntoIterator<Item = T>>(&self, iter: U) {
+        let alloc = self.allocator();
+        self.extend_inner(
+            iter.into_iter()
+                .map(|val| Box::new_in(Cons::new(val), A::clone(alloc))),
+        );
+    }
+
+    /// A fallible version of [`OnceListCore::extend`], which stops at the first node allocation
+    /// failure instead of aborting.
+    ///
+    /// The values whose nodes were allocated before the failure are still appended, and their
+    /// number is reported in the error. The value whose allocation failed and the rest of the
+    /// iterator are dropped.
+    pub fn try_extend<U: IntoIterator<Item = T>>(&self, iter: U) -> Result<(), TryExtendError> {
+        let alloc = self.allocator();
+        let mut inserted = 0;
+        let mut failed = false;
+        self.extend_inner(iter.into_iter().map_while(|val| {
+            match Box::try_new_in(Cons::new(val), A::clone(alloc)) {
+                Ok(new_cons) => {
+                    inserted += 1;
+                    Some(new_cons)
+                }
+                Err(_) => {
+                    failed = true;
+                    None
+                }
+            }
+        }));
+        if failed {
+            Err(TryExtendError { inserted })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Appends the `Ok` values from the iterator of `Result`s, stopping at the first `Err` and
+    /// returning it.
+    ///
+    /// The `Ok` values before the `Err` stay appended. The rest of the iterator is not consumed.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// let res = list.try_extend_results(["1", "x", "3"].map(str::parse::<i32>));
+    /// assert!(res.is_err());
+    /// assert_eq!(list, [1]);
+    /// ```
+    pub fn try_extend_results<E, U>(&self, iter: U) -> Result<(), E>
+    where
+        U: IntoIterator<Item = Result<T, E>>,
+    {
+        let mut first_err = None;
+        self.extend(
+            iter.into_iter()
+                .map_while(|res| res.map_err(|e| first_err = Some(e)).ok()),
+        );
+        match first_err {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Appends the clones of all the referenced values from the iterator, in order.
+    ///
+    /// This is the same as `list.extend(iter.into_iter().cloned())`.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<String>::new();
+    /// let strs = ["a".to_string(), "b".to_string()];
+    /// list.extend_cloned(&strs);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec!["a", "b"]);
+    /// ```
+    pub fn extend_cloned<'a, U>(&self, iter: U)
+    where
+        T: Clone + 'a,
+        U: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned());
+    }
+
+    /// Appends the clones of all the values in the slice, in order.
+    ///
+    /// This is the same as [`Vec::extend_from_slice`]. All the new nodes are allocated and linked
+    /// into a local chain first, and then the chain is attached to the list's tail at once, so
+    /// the tail insertion point is searched only once.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let list = OnceListWithLen::<i32>::new();
+    /// list.extend_from_slice(&[1, 2]);
+    /// list.extend_from_slice(&[3]);
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    pub fn extend_from_slice(&self, other: &[T])
+    where
+        T: Clone,
+    {
+        let alloc = self.allocator();
+        self.extend_inner(
+            other
+                .iter()
+                .map(|val| Box::new_in(Cons::new(val.clone()), A::clone(alloc))),
+        );
+    }
+
+    /// Appends the clones of all the values in the other list, in order.
+    ///
+    /// The other list can have a different allocator and cache mode, and can even be this list
+    /// itself. Only the values in the other list at the time of the call are appended, even if
+    /// the other list is growing concurrently under the `sync` feature.
+    ///
+    /// ```rust
+    /// use once_list2::{OnceList, OnceListWithLen};
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2]);
+    /// let other = OnceListWithLen::<i32>::new();
+    /// other.extend([3, 4]);
+    ///
+    /// list.extend_from_other(&other);
+    /// list.extend_from_other(&list);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4, &1, &2, &3, &4]);
+    /// ```
+    pub fn extend_from_other<A2, C2>(&self, other: &OnceListCore<T, A2, C2>)
+    where
+        T: Clone,
+        A2: Allocator,
+        C2: CacheMode<T, A2>,
+    {
+        // Take the snapshot of the other list's length first.
+        let len = other.len();
+        self.extend(other.iter().take(len).cloned());
+    }
+
+    /// Initializes the list with the values from the closure if the list is empty, and returns
+    /// an iterator over the list either way.
+    ///
+    /// This is a multi-value version of `OnceCell::get_or_init`. If the closure yields no values,
+    /// the list stays empty and the next call runs its closure again.
+    ///
+    /// Under the `sync` feature, several threads racing on an empty list may run their closures
+    /// concurrently, but the values of only one of them are appended, and the others' values
+    /// are dropped.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// assert_eq!(list.get_or_extend_with(|| [1, 2]).collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(list.get_or_extend_with(|| [3]).collect::<Vec<_>>(), vec![&1, &2]);
+    /// ```
+    pub fn get_or_extend_with<F, U>(&self, f: F) -> Iter<'_, T, A>
+    where
+        F: FnOnce() -> U,
+        U: IntoIterator<Item = T>,
+    {
+        if self.is_empty() {
+            let alloc = self.allocator();
+            let new_conses = f()
+                .into_iter()
+                .map(|val| Box::new_in(Cons::new(val), A::clone(alloc)));
+            if let Some(chain) = Chain::link(new_conses) {
+                // Only attach to the head slot. If it is taken, another thread has won the race.
+                let _ = self.try_attach_chain(&self.head_slot, chain);
+            }
+        }
+        self.iter()
+    }
+
+    /// Returns the front value, pushing the value from the closure first if the list is empty.
+    ///
+    /// This is the same as `OnceCell::get_or_init`, treating the list's front value as the cell.
+    ///
+    /// Under the `sync` feature, several threads racing on an empty list may run their closures
+    /// concurrently, but the value of only one of them is pushed, and the others' values are
+    /// dropped.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// assert_eq!(list.first_or_push(|| 1), &1);
+    /// assert_eq!(list.first_or_push(|| 2), &1);
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn first_or_push<F>(&self, f: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        if let Some(front) = self.front() {
+            return front;
+        }
+        let new_cons = Box::new_in(Cons::new(f()), A::clone(&self.alloc));
+        // Only insert to the head slot. If it is taken, another thread has won the race.
+        match self.head_slot.try_insert2(new_cons) {
+            Ok(inserted) => {
+                self.cache_mode.on_push_success(&inserted.next);
+                &inserted.val
+            }
+            Err((cur_cons, _)) => &cur_cons.val,
+        }
+    }
+
+    /// Appends the value to the list only if no equal value is in the list, and returns the
+    /// reference to the value in the list either way.
+    ///
+    /// This method is O(n). Under the `sync` feature, the check and the push are done atomically,
+    /// so two threads never push equal values to the list.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.push_unique(1);
+    /// list.push_unique(2);
+    /// list.push_unique(1);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// ```
+    pub fn push_unique(&self, val: T) -> &T
+    where
+        T: PartialEq,
+    {
+        match self.find_or_end_slot(|v| v == &val) {
+            Ok(found) => found,
+            Err(next_cell) => {
+                let new_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
+                self.push_cons_unless(next_cell, new_cons, |cur, new| cur == new)
+            }
+        }
     }
 
-    /// An almost same method with the [`std::iter::Extend::extend`],
-    /// though this method takes `&self` instead of `&mut self`.
+    /// Returns the first value matching the predicate, or pushes the value from the closure and
+    /// returns it if no value matches.
     ///
-    /// [`std::iter::Extend::extend`]: https://doc.rust-lang.org/std/iter/trait.Extend.html#tymethod.extend
-    pub fn extend<U: IntoIterator<Item = T>>(&self, iter: U) {
-        let alloc = self.allocator();
+    /// This method is O(n). Under the `sync` feature, the search and the push are done
+    /// atomically: if another thread pushes a matching value meanwhile, that value is returned
+    /// and the value from the closure is dropped.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<(&str, i32)>::new();
+    /// list.find_or_push(|(k, _)| *k == "a", || ("a", 1));
+    /// list.find_or_push(|(k, _)| *k == "b", || ("b", 2));
+    /// assert_eq!(list.find_or_push(|(k, _)| *k == "a", || ("a", 3)), &("a", 1));
+    ///
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    pub fn find_or_push<P, F>(&self, mut pred: P, make: F) -> &T
+    where
+        P: FnMut(&T) -> bool,
+        F: FnOnce() -> T,
+    {
+        match self.find_or_end_slot(&mut pred) {
+            Ok(found) => found,
+            Err(next_cell) => {
+                let new_cons = Box::new_in(Cons::new(make()), A::clone(&self.alloc));
+                self.push_cons_unless(next_cell, new_cons, |cur, _| pred(cur))
+            }
+        }
+    }
 
-        // Prefer the cached tail insertion slot when available, otherwise fall back to the head.
-        //
-        // IMPORTANT: Use `try_insert2` and retry on contention so that this method never drops
-        // values under `sync` (OnceLock) mode.
-        let mut next_cell = self.cache_mode.tail_slot_opt().unwrap_or(&self.head_slot);
+    /// Returns the first value matching the predicate, or the empty slot at the end of the list
+    /// if no value matches.
+    fn find_or_end_slot<P>(&self, mut pred: P) -> Result<&T, &NextSlot<T, A>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut next_cell = &self.head_slot;
+        while let Some(next_box) = next_cell.get() {
+            if pred(&next_box.val) {
+                return Ok(&next_box.val);
+            }
+            next_cell = &next_box.next;
+        }
+        Err(next_cell)
+    }
 
-        for val in iter {
-            let mut new_cons = Box::new_in(Cons::new(val), A::clone(alloc));
-            loop {
-                match next_cell.try_insert2(new_cons) {
-                    Ok(inserted) => {
-                        self.cache_mode.on_push_success(&inserted.next);
-                        next_cell = &inserted.next;
-                        break;
-                    }
-                    Err((cur_cons, new_cons2)) => {
-                        next_cell = &cur_cons.next;
-                        new_cons = new_cons2;
+    /// Pushes the node to the given slot or after it, unless another value is found by the
+    /// predicate `pred(existing, new)` in the nodes appended meanwhile by the other threads.
+    fn push_cons_unless<'s, P>(
+        &'s self,
+        mut next_cell: &'s NextSlot<T, A>,
+        mut new_cons: Box<Cons<T, T, A>, A>,
+        mut pred: P,
+    ) -> &'s T
+    where
+        P: FnMut(&T, &T) -> bool,
+    {
+        loop {
+            match next_cell.try_insert2(new_cons) {
+                Ok(inserted) => {
+                    self.cache_mode.on_push_success(&inserted.next);
+                    return &inserted.val;
+                }
+                Err((cur_cons, new_cons2)) => {
+                    if pred(&cur_cons.val, &new_cons2.val) {
+                        return &cur_cons.val;
                     }
+                    next_cell = &cur_cons.next;
+                    new_cons = new_cons2;
                 }
             }
         }
     }
+
+    /// Appends all the values from the iterator to the list, and returns `&self` for the
+    /// method chaining.
+    ///
+    /// This is the same as [`OnceListCore::extend`] except for the return value.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.collect_into([1, 2]).collect_into([3]);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    pub fn collect_into<U: IntoIterator<Item = T>>(&self, iter: U) -> &Self {
+        self.extend(iter);
+        self
+    }
+
+    /// Converts this list into a list of the unsized type `U`, e.g. `OnceList<dyn Trait>`,
+    /// keeping the order of the values.
+    ///
+    /// This method is O(n) and never clones the values, but it is not a zero-copy conversion:
+    /// each value is moved (memcpy) into a newly allocated node, and the old node is freed. So
+    /// this method does n allocations and n deallocations.
+    ///
+    /// The nodes cannot be re-typed in place, because a node's `next` slot points to a node of
+    /// the list's value type. For an unsized type `U`, that is a fat pointer, which is wider than
+    /// the sized list's thin one. So the value sits at a different offset in the two node types,
+    /// and an unsized node does not fit in a sized node's allocation.
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "nightly"))]
+    /// # fn main() {}
+    /// # #[cfg(feature = "nightly")]
+    /// # fn main() {
+    /// use once_list2::OnceList;
+    /// use std::fmt::Debug;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// let list: OnceList<dyn Debug> = list.into_unsized();
+    /// assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+    /// # }
+    /// ```
+    #[cfg(feature = "nightly")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "nightly")))]
+    pub fn into_unsized<U: ?Sized>(self) -> OnceList<U, A>
+    where
+        T: Unsize<U>,
+    {
+        let list = OnceList::<U, A>::new_in(A::clone(&self.alloc));
+        list.extend_unsized(self);
+        list
+    }
+
+    /// Inserts a value at the given index, shifting all the values after it to the back.
+    ///
+    /// This method is O(index).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.extend([1, 3]);
+    /// list.insert(1, 2);
+    /// list.insert(3, 4);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    /// ```
+    pub fn insert(&mut self, index: usize, val: T) {
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        for i in 0..index {
+            let Some(next_box) = next_cell.get_mut() else {
+                panic!("insert: `index` (is {index}) is out of range, the len is {i}");
+            };
+            next_cell = &mut next_box.next;
+        }
+
+        let new_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
+        let rest = next_cell.take();
+        let is_back = rest.is_none();
+        if let Some(rest) = rest {
+            let _ = new_cons.next.set(rest);
+        }
+        let _ = next_cell.set(new_cons);
+
+        if is_back {
+            if let Some(inserted) = next_cell.get() {
+                self.cache_mode.on_push_success(&inserted.next);
+            }
+        } else {
+            // Only the length is changed. The tail slot has been invalidated above.
+            let len = self.cache_mode.cached_len().map_or(0, |n| n + 1);
+            self.cache_mode.on_refresh(None, len);
+        }
+    }
+
+    /// Applies the `update` closure to the first value matching the predicate, or pushes the
+    /// value from the `make` closure if no value matches, in a single walk over the list.
+    ///
+    /// Returns `true` if an existing value is updated, or `false` if a new value is pushed.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<(&str, i32)>::new();
+    /// for key in ["a", "b", "a"] {
+    ///     list.update_or_push(|(k, _)| *k == key, |(_, count)| *count += 1, || (key, 1));
+    /// }
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&("a", 2), &("b", 1)]);
+    /// ```
+    pub fn update_or_push<P, U, F>(&mut self, mut pred: P, update: U, make: F) -> bool
+    where
+        P: FnMut(&T) -> bool,
+        U: FnOnce(&mut T),
+        F: FnOnce() -> T,
+    {
+        self.cache_mode.on_structure_change();
+
+        let mut next_cell = &mut self.head_slot;
+        while let Some(next_ref) = next_cell.get() {
+            let is_match = pred(&next_ref.val);
+            // Safe because we are sure the `next_cell` value is set.
+            let Some(next_box) = next_cell.get_mut() else {
+                unreachable!("update_or_push: next_cell had value but get_mut() returned None");
+            };
+            if is_match {
+                update(&mut next_box.val);
+                return true;
+            }
+            next_cell = &mut next_box.next;
+        }
+
+        // Not found. Push the value to the end of the list, where the walk has stopped.
+        let _ = next_cell.set(Box::new_in(Cons::new(make()), A::clone(&self.alloc)));
+        if let Some(inserted) = next_cell.get() {
+            self.cache_mode.on_push_success(&inserted.next);
+        }
+        false
+    }
+
+    /// Prepends a value to the front of the list.
+    ///
+    /// This method is O(1), and keeps the cached tail insertion slot if any.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.push_front(2);
+    /// list.push_front(1);
+    /// list.push_back(3);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// ```
+    pub fn push_front(&mut self, val: T) {
+        let new_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
+
+        // The tail is not changed by prepending to a non-empty list. But if it is the old head
+        // node's slot (i.e. a single node list), moving the node's box below invalidates the
+        // pointer, so it is re-derived after relinking.
+        let tail = self
+            .cache_mode
+            .tail_slot_opt(&self.head_slot)
+            .map(NonNull::from);
+        let tail_in_head = match (tail, self.head_slot.get()) {
+            (Some(tail), Some(head)) => ::std::ptr::eq(tail.as_ptr(), &head.next),
+            _ => false,
+        };
+
+        let Some(old_head) = self.head_slot.take() else {
+            let _ = self.head_slot.set(new_cons);
+            if let Some(inserted) = self.head_slot.get() {
+                self.cache_mode.on_push_success(&inserted.next);
+            }
+            return;
+        };
+        let len = self.cache_mode.cached_len().map_or(0, |n| n + 1);
+
+        let _ = new_cons.next.set(old_head);
+        let _ = self.head_slot.set(new_cons);
+        let tail = if tail_in_head {
+            let old_head = self.head_slot.get().and_then(|head| head.next.get());
+            old_head.map(|old_head| &old_head.next)
+        } else {
+            // Safe because the cached tail slot lives in a node owned by another node's box,
+            // which has not been moved.
+            tail.map(|p| unsafe { p.as_ref() })
+        };
+        self.cache_mode.on_refresh(tail, len);
+    }
+
+    /// Prepends all the values from the iterator to the front of the list.
+    ///
+    /// The first value of the iterator becomes the new front value. The values are linked into
+    /// a temporary chain first, and then the existing nodes are attached after it in O(1).
+    ///
+    /// If the iterator panics, the values taken from it so far are dropped, and the list is left
+    /// unchanged.
+    pub fn prepend_all<U: IntoIterator<Item = T>>(&mut self, iter: U) {
+        let mut new_head = NextSlot::new();
+        let mut next_cell = &mut new_head;
+        let mut count = 0;
+        for val in iter {
+            let new_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
+            let _ = next_cell.set(new_cons);
+            // Safe because we have just set the `next_cell` value.
+            let Some(inserted) = next_cell.get_mut() else {
+                unreachable!("prepend_all: next_cell was just set but get_mut() returned None");
+            };
+            next_cell = &mut inserted.next;
+            count += 1;
+        }
+
+        // Attach the existing nodes after the new chain.
+        if let Some(old_head) = self.head_slot.take() {
+            let _ = next_cell.set(old_head);
+        }
+        self.head_slot = new_head;
+
+        // The caches are updated only now, so a panicking iterator leaves them untouched. The
+        // old head node has been moved, so the tail slot is found again by the next push.
+        let len = self.cache_mode.cached_len().map_or(0, |n| n + count);
+        self.cache_mode.on_structure_change();
+        self.cache_mode.on_refresh(None, len);
+    }
+}
+
+impl<T, E, A: Allocator + Clone, C> OnceListCore<Result<T, E>, A, C>
+where
+    C: CacheMode<Result<T, E>, A>,
+{
+    /// Converts a list of `Result`s into a `Result` of a list, in the same way as
+    /// `Result`'s [`FromIterator`] implementation.
+    ///
+    /// Returns the first `Err` in the list if any, or a list of all the `Ok` values otherwise.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<Result<i32, String>>::new();
+    /// list.extend([Ok(1), Ok(2)]);
+    /// assert_eq!(list.collect_result().unwrap().into_iter().collect::<Vec<_>>(), vec![1, 2]);
+    ///
+    /// let list = OnceList::<Result<i32, String>>::new();
+    /// list.extend([Ok(1), Err("a".to_string()), Err("b".to_string())]);
+    /// assert_eq!(list.collect_result().err(), Some("a".to_string()));
+    /// ```
+    pub fn collect_result(self) -> Result<OnceList<T, A>, E> {
+        let list = OnceList::<T, A>::new_in(A::clone(&self.alloc));
+        let mut first_err = None;
+        list.extend(
+            self.into_iter()
+                .map_while(|res| res.map_err(|e| first_err = Some(e)).ok()),
+        );
+        match first_err {
+            Some(e) => Err(e),
+            None => Ok(list),
+        }
+    }
+}
+
+impl<T, U, A: Allocator + Clone, C> OnceListCore<(T, U), A, C>
+where
+    C: CacheMode<(T, U), A>,
+{
+    /// Converts a list of pairs into a pair of lists, keeping the order, the allocator and the
+    /// cache mode.
+    ///
+    /// Both lists are built in a single pass, without any intermediate collection.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<(i32, &str)>::new();
+    /// list.extend([(1, "a"), (2, "b")]);
+    /// let (nums, strs) = list.unzip();
+    ///
+    /// assert_eq!(nums.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(strs.iter().collect::<Vec<_>>(), vec![&"a", &"b"]);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn unzip(
+        self,
+    ) -> (
+        OnceListCore<T, A, <C as RebindAlloc<T, A>>::Rebound>,
+        OnceListCore<U, A, <C as RebindAlloc<U, A>>::Rebound>,
+    )
+    where
+        C: RebindAlloc<T, A> + RebindAlloc<U, A>,
+    {
+        let lhs = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(&self.alloc),
+            cache_mode: <C as RebindAlloc<T, A>>::Rebound::default(),
+        };
+        let rhs = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(&self.alloc),
+            cache_mode: <C as RebindAlloc<U, A>>::Rebound::default(),
+        };
+
+        // The new lists are not shared yet, so the nodes are linked without any contention.
+        let mut lhs_cell = &lhs.head_slot;
+        let mut rhs_cell = &rhs.head_slot;
+        let mut count = 0;
+        for (t, u) in self {
+            let _ = lhs_cell.set(Box::new_in(Cons::new(t), A::clone(&lhs.alloc)));
+            let _ = rhs_cell.set(Box::new_in(Cons::new(u), A::clone(&rhs.alloc)));
+            let (Some(lhs_box), Some(rhs_box)) = (lhs_cell.get(), rhs_cell.get()) else {
+                unreachable!("unzip: the cells were just set but get() returned None");
+            };
+            lhs_cell = &lhs_box.next;
+            rhs_cell = &rhs_box.next;
+            count += 1;
+        }
+        if count > 0 {
+            lhs.cache_mode.on_extend_success(lhs_cell, count);
+            rhs.cache_mode.on_extend_success(rhs_cell, count);
+        }
+        (lhs, rhs)
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone, C, C2> OnceListCore<OnceListCore<T, A, C2>, A, C>
+where
+    C: CacheMode<OnceListCore<T, A, C2>, A>,
+    C2: CacheMode<T, A> + Default,
+{
+    /// Consumes the list of lists, and splices the inner lists end-to-end into a single list.
+    ///
+    /// The inner lists' nodes are only relinked, so no value is reallocated. Note that those
+    /// nodes stay allocated by the inner lists' allocator instances. The result has a clone of
+    /// the outer list's allocator and the inner lists' cache mode.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<OnceList<i32>>::new();
+    /// list.push(OnceList::from([1, 2]));
+    /// list.push(OnceList::new());
+    /// list.push(OnceList::from([3]));
+    ///
+    /// assert_eq!(list.flatten().iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    pub fn flatten(self) -> OnceListCore<T, A, C2> {
+        let mut flat = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc: A::clone(&self.alloc),
+            cache_mode: C2::default(),
+        };
+        for mut inner in self {
+            // The inner list has been moved out of its node, which may have retagged the node
+            // holding the cached tail slot. Find the tail by walking instead.
+            inner.cache_mode.on_structure_change();
+            flat.append(&mut inner);
+        }
+        flat
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// A fallible version of [`Clone::clone`], which returns an error instead of aborting when
+    /// an allocation fails.
+    ///
+    /// On failure, the partially built clone is freed before returning the error.
+    pub fn try_clone(&self) -> Result<Self, AllocError> {
+        self.try_clone_in(self.alloc.clone())
+    }
+
+    /// Same as [`OnceListCore::try_clone`], but the clone allocates its nodes with the given
+    /// allocator.
+    pub fn try_clone_in(&self, alloc: A) -> Result<Self, AllocError> {
+        let list = self.new_empty_like_in(alloc);
+        let mut alloc_error = None;
+        list.extend_inner(self.iter().map_while(|val| {
+            Box::try_new_in(Cons::new(val.clone()), A::clone(&list.alloc))
+                .map_err(|e| alloc_error = Some(e))
+                .ok()
+        }));
+        match alloc_error {
+            // `list` is dropped here, freeing the nodes cloned so far.
+            Some(e) => Err(e),
+            None => Ok(list),
+        }
+    }
+
+    /// Clones the list into a new list which allocates its nodes with the given allocator,
+    /// keeping the order and the cache mode.
+    ///
+    /// Unlike [`Clone::clone`], the allocator type can differ, e.g. to copy an arena allocated
+    /// list into the global heap before the arena is reset.
+    ///
+    /// ```rust
+    /// use once_list2::OnceListWithLen;
+    ///
+    /// let list = OnceListWithLen::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// let cloned = list.clone_in(list.allocator().clone());
+    ///
+    /// assert_eq!(cloned.len(), 3);
+    /// assert_eq!(cloned, list);
+    /// ```
+    pub fn clone_in<B>(&self, alloc: B) -> OnceListCore<T, B, C::Rebound>
+    where
+        B: Allocator + Clone,
+        C: RebindAlloc<T, B>,
+    {
+        let list = OnceListCore {
+            head_slot: NextSlot::new(),
+            alloc,
+            cache_mode: C::Rebound::default(),
+        };
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, C> Clone for OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Clones the list by cloning each value into a new node.
+    ///
+    /// The nodes are linked iteratively in a single pass, so this does not recurse even for a
+    /// long list. If a value's `clone()` panics, the partially built list is dropped.
+    fn clone(&self) -> Self {
+        let list = self.new_empty_like();
+        list.extend(self.iter().cloned());
+        list
+    }
 }
 
 impl<T: ?Sized, A: Allocator + Default, C: Default> Default for OnceListCore<T, A, C> {
@@ -602,6 +3215,55 @@ where
 
 impl<T: ?Sized + Eq, A: Allocator, C> Eq for OnceListCore<T, A, C> where C: CacheMode<T, A> {}
 
+/// Compares the lists lexicographically, in the same manner as `Vec`.
+impl<T: ?Sized + PartialOrd, A: Allocator, C> PartialOrd for OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+/// Compares the lists lexicographically, in the same manner as `Vec`.
+impl<T: ?Sized + Ord, A: Allocator, C> Ord for OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// Implements `PartialEq` in both directions between the list and a sequence type.
+macro_rules! impl_partial_eq_with_seq {
+    ($([$($gen:tt)*] $seq:ty;)*) => {$(
+        impl<T, U, A: Allocator, C, $($gen)*> PartialEq<$seq> for OnceListCore<T, A, C>
+        where
+            T: PartialEq<U>,
+        {
+            fn eq(&self, other: &$seq) -> bool {
+                self.iter().eq(other.iter())
+            }
+        }
+
+        impl<T, U, A: Allocator, C, $($gen)*> PartialEq<OnceListCore<T, A, C>> for $seq
+        where
+            U: PartialEq<T>,
+        {
+            fn eq(&self, other: &OnceListCore<T, A, C>) -> bool {
+                self.iter().eq(other.iter())
+            }
+        }
+    )*};
+}
+
+impl_partial_eq_with_seq! {
+    [] [U];
+    [const N: usize] [U; N];
+    [] Vec<U>;
+}
+
 impl<T: ?Sized + Hash, A: Allocator, C> Hash for OnceListCore<T, A, C>
 where
     C: CacheMode<T, A>,
@@ -614,6 +3276,49 @@ where
     }
 }
 
+/// Note that indexing is O(index), like [`OnceListCore::get`].
+///
+/// # Panics
+///
+/// Panics if the index is out of range.
+///
+/// ```rust
+/// use once_list2::OnceList;
+///
+/// let mut list = OnceList::<i32>::new();
+/// list.extend([1, 2, 3]);
+/// list[1] = 20;
+/// assert_eq!(list[1], 20);
+/// ```
+impl<T: ?Sized, A: Allocator, C> Index<usize> for OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.try_get(index) {
+            Ok(val) => val,
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> IndexMut<usize> for OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        // Count the visited values, which is the length if the index is out of range.
+        let mut len = 0;
+        let found = self.iter_mut().inspect(|_| len += 1).nth(index);
+        match found {
+            Some(val) => val,
+            None => panic!("{}", IndexError { index, len }),
+        }
+    }
+}
+
 impl<T> FromIterator<T> for OnceListCore<T, Global, NoCache> {
     fn from_iter<U: IntoIterator<Item = T>>(iter: U) -> Self {
         let list = Self::new();
@@ -636,6 +3341,33 @@ impl<T> FromIterator<T> for OnceListCore<T, Global, NoCache> {
     }
 }
 
+impl<T> From<Vec<T>> for OnceListCore<T, Global, NoCache> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for OnceListCore<T, Global, NoCache> {
+    fn from(array: [T; N]) -> Self {
+        array.into_iter().collect()
+    }
+}
+
+impl<T: Clone> From<&[T]> for OnceListCore<T, Global, NoCache> {
+    fn from(slice: &[T]) -> Self {
+        slice.iter().cloned().collect()
+    }
+}
+
+impl<T, A: Allocator, C> From<OnceListCore<T, A, C>> for Vec<T>
+where
+    C: CacheMode<T, A>,
+{
+    fn from(list: OnceListCore<T, A, C>) -> Self {
+        list.into_vec()
+    }
+}
+
 impl<T, A: Allocator, C> IntoIterator for OnceListCore<T, A, C>
 where
     C: CacheMode<T, A>,
@@ -644,7 +3376,10 @@ where
     type IntoIter = IntoIter<T, A>;
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter(self.head_slot)
+        IntoIter {
+            next_slot: self.head_slot,
+            alloc: self.alloc,
+        }
     }
 }
 
@@ -657,7 +3392,10 @@ impl<'a, T: ?Sized, A: Allocator, C> IntoIterator for &'a OnceListCore<T, A, C>
     }
 }
 
-impl<'a, T: ?Sized, A: Allocator, C> IntoIterator for &'a mut OnceListCore<T, A, C> {
+impl<'a, T: ?Sized, A: Allocator, C> IntoIterator for &'a mut OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
     type Item = &'a mut T;
     type IntoIter = IterMut<'a, T, A>;
 
@@ -677,3 +3415,15 @@ where
         OnceListCore::<T, A, C>::extend(&*self, iter);
     }
 }
+
+impl<'a, T: Clone + 'a, A: Allocator + Clone, C> Extend<&'a T> for OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Appends the clones of the referenced values.
+    ///
+    /// Use the [`OnceListCore::extend_cloned`] method instead if you want to use `&self`.
+    fn extend<U: IntoIterator<Item = &'a T>>(&mut self, iter: U) {
+        self.extend_cloned(iter);
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::allocator_api2::alloc::{Allocator, Global};
+use ::allocator_api2::boxed::Box;
+use ::std::fmt::{self, Debug};
+use ::std::ops::Deref;
+use ::std::pin::Pin;
+
+use crate::cache_mode::{CacheMode, NoCache};
+use crate::cons::Cons;
+use crate::once_list::OnceListCore;
+
+/// A list whose values are pinned: they are never moved in memory until they are dropped.
+///
+/// Each value of a [`OnceListCore`] lives in its own heap node, and never moves while it is in
+/// the list. But the `&mut self` methods of the list like [`OnceListCore::remove`] can move the
+/// values out, so the list itself cannot hand out [`Pin`]ned references. This wrapper removes
+/// those methods: it only exposes the shared [`OnceListCore`] methods (via [`Deref`]), which never
+/// move the values, plus the pinned accessors and [`PinnedOnceList::clear`] which drops the
+/// values in place. So the values can be self-referential or intrusive, e.g. `!Unpin` futures.
+///
+/// ```rust
+/// use once_list2::OnceList;
+/// use std::marker::PhantomPinned;
+/// use std::pin::Pin;
+///
+/// struct Node {
+///     val: i32,
+///     _pin: PhantomPinned,
+/// }
+///
+/// let list = OnceList::<Node>::new().into_pinned();
+/// let node: Pin<&Node> = list.push_pinned(Node { val: 1, _pin: PhantomPinned });
+/// let addr = &*node as *const Node;
+///
+/// list.push_pinned(Node { val: 2, _pin: PhantomPinned });
+/// let first = list.iter_pinned().next().unwrap();
+/// assert_eq!(&*first as *const Node, addr);
+/// assert_eq!(list.iter().map(|n| n.val).collect::<Vec<_>>(), vec![1, 2]);
+/// ```
+pub struct PinnedOnceList<T: ?Sized, A: Allocator = Global, C = NoCache> {
+    list: OnceListCore<T, A, C>,
+}
+
+impl<T: ?Sized, A: Allocator, C> PinnedOnceList<T, A, C> {
+    /// Returns an iterator over the pinned references to the values in the list.
+    ///
+    /// Like [`OnceListCore::iter`], the iterator observes the values pushed later.
+    pub fn iter_pinned(&self) -> impl Iterator<Item = Pin<&T>> + '_ {
+        // Safe because the values never move until they are dropped, see the type's document.
+        self.list
+            .iter()
+            .map(|val| unsafe { Pin::new_unchecked(val) })
+    }
+
+    /// Returns an iterator over the pinned mutable references to the values in the list.
+    pub fn iter_mut_pinned(&mut self) -> impl Iterator<Item = Pin<&mut T>> + '_
+    where
+        C: CacheMode<T, A>,
+    {
+        // Safe because the values never move until they are dropped, see the type's document.
+        self.list
+            .iter_mut()
+            .map(|val| unsafe { Pin::new_unchecked(val) })
+    }
+
+    /// Clears the list, dropping all the values in place.
+    pub fn clear(&mut self)
+    where
+        C: CacheMode<T, A>,
+    {
+        self.list.clear();
+    }
+
+    /// Unwraps the list. This is only possible for the `Unpin` values, which do not care about
+    /// being moved.
+    pub fn into_inner(self) -> OnceListCore<T, A, C>
+    where
+        T: Unpin,
+    {
+        self.list
+    }
+}
+
+impl<T, A: Allocator + Clone, C> PinnedOnceList<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Appends a value to the back of the list, and returns the pinned reference to that value.
+    ///
+    /// Note that this method takes `&self`, not `&mut self`.
+    pub fn push_pinned(&self, val: T) -> Pin<&T> {
+        let boxed_cons = Box::new_in(Cons::new(val), A::clone(self.list.allocator()));
+        let val = self.list.push_inner(boxed_cons, |c| c);
+        // Safe because the values never move until they are dropped, see the type's document.
+        unsafe { Pin::new_unchecked(val) }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> Deref for PinnedOnceList<T, A, C> {
+    type Target = OnceListCore<T, A, C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+}
+
+impl<T: ?Sized + Debug, A: Allocator, C> Debug for PinnedOnceList<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.list, f)
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
+    /// Converts the list into a [`PinnedOnceList`], whose values are never moved until they are
+    /// dropped.
+    pub fn into_pinned(self) -> PinnedOnceList<T, A, C> {
+        PinnedOnceList { list: self }
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A low-level node handle API, for building custom index structures over a list.
+//!
+//! - [`NodeRef`] is a borrowed handle to a node, which can navigate to the next node.
+//! - [`NodeId`] is an opaque identity of a node, which does not borrow the list. It can be
+//!   stored in a map, and later be used to look up the node by [`OnceListCore::get_by_id`] or
+//!   to remove it by [`OnceListCore::remove_node`].
+//!
+//! ## Holding `NodeId`s across structural edits
+//!
+//! A `NodeId` is never dereferenced, so holding it across any edit of the list is memory safe.
+//! However, once the node is removed (or the list is cleared or dropped), its memory can be
+//! reused by a newly pushed node, and then the stale `NodeId` would identify that new node.
+//! So make sure to discard the `NodeId`s of the removed nodes.
+//!
+//! In debug builds, each node also has a unique serial number, which is stored in its `NodeId`.
+//! The methods taking a `NodeId` panic if the id's address matches a node with another serial
+//! number, i.e. the id is stale.
+//!
+//! ```rust
+//! use once_list2::OnceList;
+//! use std::collections::HashMap;
+//!
+//! let mut list = OnceList::<(&str, i32)>::new();
+//! let mut index = HashMap::new();
+//! for (key, val) in [("a", 1), ("b", 2), ("c", 3)] {
+//!     let node = list.push_back_node((key, val));
+//!     index.insert(key, node.id());
+//! }
+//!
+//! let id = index.remove("b").unwrap();
+//! assert_eq!(list.remove_node(id), Some(("b", 2)));
+//! assert_eq!(list.iter().map(|(k, _)| *k).collect::<String>(), "ac");
+//! ```
+
+use ::allocator_api2::alloc::{Allocator, Global};
+use ::allocator_api2::boxed::Box;
+use ::std::fmt::{self, Debug};
+use ::std::hash::{Hash, Hasher};
+use ::std::marker::PhantomData;
+use ::std::ptr::NonNull;
+
+use crate::cache_mode::{CacheMode, NextSlot};
+use crate::cons::Cons;
+use crate::iter::Iter;
+use crate::once_list::OnceListCore;
+
+/// A borrowed handle to a node in a list.
+pub struct NodeRef<'a, T: ?Sized, A: Allocator = Global> {
+    cons: &'a Cons<T, T, A>,
+}
+
+impl<'a, T: ?Sized, A: Allocator> NodeRef<'a, T, A> {
+    /// Returns the reference to the value of this node.
+    pub fn value(&self) -> &'a T {
+        &self.cons.val
+    }
+
+    /// Returns the next node, or `None` if this is the last node at this moment.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&self) -> Option<NodeRef<'a, T, A>> {
+        self.cons.next.get().map(|cons| NodeRef { cons })
+    }
+
+    /// Returns an iterator over the values from this node (inclusive) to the end of the list.
+    ///
+    /// Together with [`OnceListCore::push_back_node`], this allows resuming a traversal from a
+    /// pushed value without re-walking from the head.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.push(1);
+    /// let node = list.push_back_node(2);
+    /// list.push(3);
+    /// assert_eq!(node.iter_from().collect::<Vec<_>>(), vec![&2, &3]);
+    /// ```
+    pub fn iter_from(&self) -> impl Iterator<Item = &'a T> + 'a {
+        ::std::iter::once(&self.cons.val).chain(Iter::new(&self.cons.next))
+    }
+
+    /// Returns an iterator over the values after this node (exclusive).
+    ///
+    /// This is O(new values), i.e. it never re-scans from the head. Like [`Iter`], the iterator
+    /// observes the values pushed later, so keeping the handle to the last seen node gives
+    /// "what's new since I last looked" semantics for an append-only event log.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let log = OnceList::<&str>::new();
+    /// let seen = log.push_back_node("started");
+    /// log.push("a");
+    /// log.push("b");
+    /// assert_eq!(seen.iter_after().collect::<Vec<_>>(), vec![&"a", &"b"]);
+    /// ```
+    pub fn iter_after(&self) -> Iter<'a, T, A> {
+        Iter::new(&self.cons.next)
+    }
+
+    /// Returns the opaque identity of this node.
+    pub fn id(&self) -> NodeId<T, A> {
+        NodeId {
+            addr: ::std::ptr::addr_of!(self.cons.val).cast::<u8>().addr(),
+            #[cfg(debug_assertions)]
+            serial: self.cons.serial,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Clone for NodeRef<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Copy for NodeRef<'_, T, A> {}
+
+impl<T: ?Sized + Debug, A: Allocator> Debug for NodeRef<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NodeRef").field(&self.value()).finish()
+    }
+}
+
+/// An opaque identity of a node in a list, which does not borrow the list.
+///
+/// See the [module documentation](self) for the caveat of holding it across removals.
+pub struct NodeId<T: ?Sized, A: Allocator = Global> {
+    /// The address of the node's value. Only used for comparisons, never dereferenced.
+    addr: usize,
+    /// The node's serial number, to detect a stale id in debug builds.
+    #[cfg(debug_assertions)]
+    serial: usize,
+    _phantom: PhantomData<fn(&T, &A)>,
+}
+
+impl<T: ?Sized, A: Allocator> NodeId<T, A> {
+    /// Returns `true` if the given reference points to the value of the node with this id.
+    pub fn is_value_of(&self, val: &T) -> bool {
+        (val as *const T).cast::<u8>().addr() == self.addr
+    }
+
+    /// Returns `true` if this is the id of the given node.
+    ///
+    /// In debug builds, panics if the address matches but the node is not the one this id was
+    /// taken from.
+    fn is_id_of(&self, cons: &Cons<T, T, A>) -> bool {
+        let is_match = self.is_value_of(&cons.val);
+        #[cfg(debug_assertions)]
+        assert!(
+            !is_match || self.serial == cons.serial,
+            "stale NodeId: its node has been removed, and the address is reused by another node"
+        );
+        is_match
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Clone for NodeId<T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Copy for NodeId<T, A> {}
+
+impl<T: ?Sized, A: Allocator> PartialEq for NodeId<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr == other.addr
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Eq for NodeId<T, A> {}
+
+impl<T: ?Sized, A: Allocator> Hash for NodeId<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr.hash(state);
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Debug for NodeId<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NodeId").field(&self.addr).finish()
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C> {
+    /// Returns the handle to the first node in the list.
+    pub fn front_node(&self) -> Option<NodeRef<'_, T, A>> {
+        self.head_slot.get().map(|cons| NodeRef { cons })
+    }
+
+    /// Returns the reference to the value of the node with the given id.
+    ///
+    /// This method is O(n), comparing the id with each node's address. So a stale id is never
+    /// dereferenced: it just returns `None`, unless the memory has been reused by a newly pushed
+    /// node (see the [module documentation](self)).
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let mut list = OnceList::<i32>::new();
+    /// list.push(1);
+    /// let id = list.push_back_node(2).id();
+    ///
+    /// assert_eq!(list.get_by_id(id), Some(&2));
+    /// *list.get_mut_by_id(id).unwrap() = 20;
+    /// assert_eq!(list.remove_node(id), Some(20));
+    /// assert_eq!(list.get_by_id(id), None);
+    /// ```
+    pub fn get_by_id(&self, id: NodeId<T, A>) -> Option<&T> {
+        let mut next_cell = &self.head_slot;
+        while let Some(cons) = next_cell.get() {
+            if id.is_id_of(cons) {
+                return Some(&cons.val);
+            }
+            next_cell = &cons.next;
+        }
+        None
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Removes the node with the given id, and returns its value as a box.
+    ///
+    /// This method is O(n). Returns `None` if no node in the list has the id.
+    pub fn remove_node_into_box(&mut self, id: NodeId<T, A>) -> Option<Box<T, A>> {
+        // In debug builds, check that the id is not stale before removing by the address.
+        #[cfg(debug_assertions)]
+        let _ = self.get_by_id(id);
+        self.remove_into_box(|val| id.is_value_of(val))
+    }
+
+    /// Returns the mutable reference to the value of the node with the given id.
+    ///
+    /// This method is O(n). See [`OnceListCore::get_by_id`] for the details.
+    pub fn get_mut_by_id(&mut self, id: NodeId<T, A>) -> Option<&mut T> {
+        let mut next_cell = self.nodes_mut();
+        loop {
+            let cons = next_cell.get_mut()?;
+            if id.is_id_of(cons) {
+                return Some(&mut cons.val);
+            }
+            next_cell = &mut cons.next;
+        }
+    }
+}
+
+impl<T, A: Allocator, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Removes the node with the given id, and returns its value.
+    ///
+    /// This method is O(n). Returns `None` if no node in the list has the id.
+    pub fn remove_node(&mut self, id: NodeId<T, A>) -> Option<T> {
+        // In debug builds, check that the id is not stale before removing by the address.
+        #[cfg(debug_assertions)]
+        let _ = self.get_by_id(id);
+        self.remove(|val| id.is_value_of(val))
+    }
+}
+
+impl<T, A: Allocator + Clone, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A>,
+{
+    /// Appends a value to the back of the list, and returns the handle to the new node.
+    ///
+    /// Note that this method takes `&self`, not `&mut self`.
+    pub fn push_back_node(&self, val: T) -> NodeRef<'_, T, A> {
+        let boxed_cons = Box::new_in(Cons::new(val), A::clone(&self.alloc));
+        NodeRef {
+            cons: self.push_cons(boxed_cons),
+        }
+    }
+}
+
+impl<T, A: Allocator, C> OnceListCore<T, A, C> {
+    /// Decomposes the list into the raw pointer to its first node (`None` if the list is empty)
+    /// and its allocator.
+    ///
+    /// The pointer is opaque: it can only be passed back to [`OnceListCore::from_raw_parts`].
+    /// The nodes are not freed until the list is reconstructed and dropped. This method is O(1).
+    ///
+    /// The returned allocator is the list's one, which is not always the one that allocated the
+    /// first node: [`OnceListCore::append`] into an empty list, [`OnceListCore::merge`] and
+    /// `flatten` link in the nodes of other lists, which stay allocated by those lists'
+    /// allocator instances. The other nodes keep their own allocators, but the first node's one
+    /// is dropped here. See the safety section of [`OnceListCore::from_raw_parts`].
+    ///
+    /// ```rust
+    /// use once_list2::{OnceList, OnceListWithLen};
+    ///
+    /// let list = OnceList::from([1, 2, 3]);
+    /// let (head, alloc) = list.into_raw_parts();
+    ///
+    /// // Reconstruct the nodes as a list in another cache mode.
+    /// let list = unsafe { OnceListWithLen::<i32>::from_raw_parts(head, alloc) };
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(list, [1, 2, 3]);
+    /// ```
+    pub fn into_raw_parts(self) -> (Option<NonNull<u8>>, A) {
+        let OnceListCore {
+            mut head_slot,
+            alloc,
+            ..
+        } = self;
+        let head = head_slot.take().map(|head_box| {
+            // The caller of `from_raw_parts` provides an allocator which can free the head node.
+            let (raw_cons, _head_alloc) = Box::into_raw_with_allocator(head_box);
+            match NonNull::new(raw_cons) {
+                Some(p) => p.cast::<u8>(),
+                None => unreachable!("Box::into_raw_with_allocator returned null"),
+            }
+        });
+        (head, alloc)
+    }
+}
+
+impl<T, A: Allocator + Clone, C> OnceListCore<T, A, C>
+where
+    C: CacheMode<T, A> + Default,
+{
+    /// Reconstructs a list from the raw parts returned by [`OnceListCore::into_raw_parts`].
+    ///
+    /// The cache mode may differ from the original list's one. The list is walked once to
+    /// re-establish the caches, so this method is O(n).
+    ///
+    /// # Safety
+    ///
+    /// - `head` must be returned by [`OnceListCore::into_raw_parts`] of a list with the same
+    ///   value type `T` and allocator type `A` (in any cache mode), or be `None`.
+    /// - `alloc` must be able to free the first node. The allocator returned together with
+    ///   `head` (or a clone of it) does so only if the first node was allocated by that list's
+    ///   allocator instance, and not moved in from another list by [`OnceListCore::append`],
+    ///   [`OnceListCore::merge`] or `flatten`. Otherwise `alloc` must be able to free the memory
+    ///   of the allocator instance which allocated the first node. The other nodes are freed by
+    ///   their own allocators.
+    /// - The raw parts must be reconstructed only once.
+    pub unsafe fn from_raw_parts(head: Option<NonNull<u8>>, alloc: A) -> Self {
+        let list = Self {
+            head_slot: NextSlot::new(),
+            alloc,
+            cache_mode: C::default(),
+        };
+        if let Some(head) = head {
+            // Safe because the caller guarantees the pointer comes from `into_raw_parts`.
+            let head_box = unsafe {
+                Box::from_raw_in(head.cast::<Cons<T, T, A>>().as_ptr(), A::clone(&list.alloc))
+            };
+            let _ = list.head_slot.set(head_box);
+        }
+        list.refresh_caches();
+        list
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::allocator_api2::alloc::Allocator;
+use ::allocator_api2::boxed::Box;
+
+use crate::cache_mode::CacheMode;
+use crate::cons::Cons;
+use crate::once_list::OnceListCore;
+
+impl<T, A: Allocator, C> OnceListCore<[T], A, C>
+where
+    C: CacheMode<[T], A>,
+{
+    /// Removes the first slice in the list whose length is exactly `N`, and returns it as an array.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    /// use allocator_api2::boxed::Box;
+    ///
+    /// let mut list = OnceList::<[i32]>::new();
+    /// list.push_boxed(Box::from(&[1][..]));
+    /// list.push_boxed(Box::from(&[2, 3][..]));
+    ///
+    /// assert_eq!(list.remove_array::<2>(), Some([2, 3]));
+    /// assert_eq!(list.remove_array::<2>(), None);
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn remove_array<const N: usize>(&mut self) -> Option<[T; N]> {
+        self.remove_inner(|s| s.len() == N, Cons::box_into_array)
+    }
+
+    /// Removes the first slice in the list that matches the predicate, and returns it as a boxed slice.
+    ///
+    /// Note that the returned box is newly allocated, and the slice elements are moved (memcpy) into it.
+    pub fn remove_slice_where<P>(&mut self, pred: P) -> Option<Box<[T], A>>
+    where
+        P: FnMut(&[T]) -> bool,
+    {
+        self.remove_inner(pred, Cons::box_into_inner_slice)
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, C> OnceListCore<[T], A, C>
+where
+    C: CacheMode<[T], A>,
+{
+    /// Pushes a clone of the slice to the list, and returns the reference to that value.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<[i32]>::new();
+    /// list.push_slice(&[1, 2]);
+    /// list.push_slice(&[3]);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&[1, 2][..], &[3]]);
+    /// ```
+    pub fn push_slice(&self, val: &[T]) -> &[T] {
+        let boxed_cons = Cons::new_boxed_slice_clone(val, A::clone(&self.alloc));
+        self.push_inner(boxed_cons, |s| s)
+    }
+
+    /// Pushes a clone of each slice to the list.
+    ///
+    /// Like [`OnceListCore::extend`], this method finds the tail insertion point only once.
+    pub fn extend_slices<S, I>(&self, iter: I)
+    where
+        S: AsRef<[T]>,
+        I: IntoIterator<Item = S>,
+    {
+        self.extend_inner(
+            iter.into_iter()
+                .map(|val| Cons::new_boxed_slice_clone(val.as_ref(), A::clone(&self.alloc))),
+        );
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, C> Clone for OnceListCore<[T], A, C>
+where
+    C: CacheMode<[T], A>,
+{
+    /// Clones the list by cloning each slice element-wise into a new node.
+    fn clone(&self) -> Self {
+        let list = self.new_empty_like();
+        list.extend_slices(self.iter());
+        list
+    }
+}
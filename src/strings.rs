@@ -0,0 +1,213 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ::allocator_api2::alloc::{Allocator, Global};
+use ::allocator_api2::boxed::Box;
+use ::std::boxed::Box as StdBox;
+use ::std::ffi::{CStr, OsStr};
+use ::std::fmt::{self, Display};
+use ::std::path::Path;
+
+use crate::cache_mode::{CacheMode, NextSlot};
+use crate::cons::Cons;
+use crate::iter::Iter;
+use crate::once_list::OnceListCore;
+
+/// Reinterprets a boxed cons of bytes as a boxed cons of the string-like type `T`.
+///
+/// # Safety
+/// `cast` must only change the pointee type (keeping the address and the length metadata),
+/// and the bytes must be a valid representation of `T`.
+unsafe fn cast_bytes_cons<T: ?Sized, A: Allocator>(
+    bytes_cons: Box<Cons<[u8], T, A>, A>,
+    cast: impl FnOnce(*mut Cons<[u8], T, A>) -> *mut Cons<T, T, A>,
+) -> Box<Cons<T, T, A>, A> {
+    let (bytes_ptr, alloc) = Box::into_raw_with_allocator(bytes_cons);
+    unsafe { Box::from_raw_in(cast(bytes_ptr), alloc) }
+}
+
+impl<A: Allocator + Clone, C> OnceListCore<str, A, C>
+where
+    C: CacheMode<str, A>,
+{
+    /// Pushes a copy of the string to the list, and returns the reference to that value.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<str>::new();
+    /// list.push_str("hello");
+    /// list.push_str("world");
+    ///
+    /// assert!(list.contains("world"));
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec!["hello", "world"]);
+    /// ```
+    pub fn push_str(&self, val: &str) -> &str {
+        self.push_inner(Self::new_str_cons(val, A::clone(&self.alloc)), |s| s)
+    }
+
+    /// Pushes a copy of each string to the list.
+    ///
+    /// Like [`OnceListCore::extend`], this method finds the tail insertion point only once.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<str>::new();
+    /// list.extend_strs(["hello", "world"]);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec!["hello", "world"]);
+    /// ```
+    pub fn extend_strs<S, I>(&self, iter: I)
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = S>,
+    {
+        self.extend_inner(
+            iter.into_iter()
+                .map(|val| Self::new_str_cons(val.as_ref(), A::clone(&self.alloc))),
+        );
+    }
+
+    fn new_str_cons(val: &str, alloc: A) -> Box<Cons<str, str, A>, A> {
+        let bytes_cons = Cons::new_boxed_slice_copy(val.as_bytes(), alloc);
+        // Safe because `str` is represented as `[u8]`, and the bytes are copied from a `str`.
+        unsafe { cast_bytes_cons(bytes_cons, |p| p as *mut Cons<str, str, A>) }
+    }
+}
+
+impl<A: Allocator + Clone, C> OnceListCore<OsStr, A, C>
+where
+    C: CacheMode<OsStr, A>,
+{
+    /// Pushes a copy of the OS string to the list, and returns the reference to that value.
+    pub fn push_os_str(&self, val: &OsStr) -> &OsStr {
+        let boxed_cons = new_cons_from_std_box(StdBox::from(val), A::clone(&self.alloc));
+        self.push_inner(boxed_cons, |s| s)
+    }
+}
+
+impl<A: Allocator + Clone, C> OnceListCore<Path, A, C>
+where
+    C: CacheMode<Path, A>,
+{
+    /// Pushes a copy of the path to the list, and returns the reference to that value.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    /// use std::path::Path;
+    ///
+    /// let list = OnceList::<Path>::new();
+    /// list.push_path(Path::new("/usr/bin"));
+    ///
+    /// assert!(list.contains(Path::new("/usr/bin")));
+    /// ```
+    pub fn push_path(&self, val: &Path) -> &Path {
+        let boxed_cons = new_cons_from_std_box(StdBox::from(val), A::clone(&self.alloc));
+        self.push_inner(boxed_cons, |s| s)
+    }
+}
+
+impl<A: Allocator + Clone, C> OnceListCore<CStr, A, C>
+where
+    C: CacheMode<CStr, A>,
+{
+    /// Pushes a copy of the C string to the list, and returns the reference to that value.
+    ///
+    /// The copied value keeps the trailing NUL byte.
+    pub fn push_c_str(&self, val: &CStr) -> &CStr {
+        let boxed_cons = new_cons_from_std_box(StdBox::from(val), A::clone(&self.alloc));
+        self.push_inner(boxed_cons, |s| s)
+    }
+}
+
+/// Moves a value boxed by the standard library into a new boxed cons.
+///
+/// Unlike `str`, the layouts of `OsStr`, `Path` and `CStr` are not guaranteed, so these values
+/// are built by the standard library's own conversions, and then moved as opaque unsized values
+/// like [`OnceListCore::push_boxed`] does. This costs one more copy and allocation than the
+/// `str` path.
+fn new_cons_from_std_box<T: ?Sized, A: Allocator>(
+    val: StdBox<T>,
+    alloc: A,
+) -> Box<Cons<T, T, A>, A> {
+    // Safe because `std::boxed::Box` allocates its value with the global allocator, which is
+    // what `Global` allocates with.
+    let val = unsafe { Box::from_raw_in(StdBox::into_raw(val), Global) };
+    Cons::new_boxed_from_box(val, alloc)
+}
+
+impl<A: Allocator + Clone, C> Clone for OnceListCore<str, A, C>
+where
+    C: CacheMode<str, A>,
+{
+    /// Clones the list by copying each string into a new node.
+    fn clone(&self) -> Self {
+        let list = self.new_empty_like();
+        list.extend_strs(self.iter());
+        list
+    }
+}
+
+impl<T: ?Sized + Display, A: Allocator, C> OnceListCore<T, A, C> {
+    /// Renders the values into a `String`, separated by the given separator.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<i32>::new();
+    /// list.extend([1, 2, 3]);
+    /// assert_eq!(list.join(", "), "1, 2, 3");
+    /// ```
+    pub fn join(&self, separator: &str) -> String {
+        self.display(separator).to_string()
+    }
+
+    /// Returns an adapter which implements [`Display`] by rendering the values separated by the
+    /// given separator, without building an intermediate `String`.
+    ///
+    /// ```rust
+    /// use once_list2::OnceList;
+    ///
+    /// let list = OnceList::<str>::new();
+    /// list.extend_strs(["a", "b"]);
+    /// assert_eq!(format!("[{}]", list.display(" | ")), "[a | b]");
+    /// ```
+    pub fn display<'a>(&'a self, separator: &'a str) -> Joined<'a, T, A> {
+        Joined {
+            head_slot: &self.head_slot,
+            separator,
+        }
+    }
+}
+
+/// A [`Display`] adapter rendering the values of a [`crate::OnceList`] separated by a separator.
+///
+/// This struct is created by [`OnceListCore::display`].
+pub struct Joined<'a, T: ?Sized, A: Allocator> {
+    head_slot: &'a NextSlot<T, A>,
+    separator: &'a str,
+}
+
+impl<T: ?Sized + Display, A: Allocator> Display for Joined<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, val) in Iter::new(self.head_slot).enumerate() {
+            if i > 0 {
+                f.write_str(self.separator)?;
+            }
+            val.fmt(f)?;
+        }
+        Ok(())
+    }
+}
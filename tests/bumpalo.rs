@@ -0,0 +1,123 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "bumpalo")]
+#![cfg_attr(feature = "nightly", feature(allocator_api))]
+
+use ::bumpalo::Bump;
+use ::once_list2::{OnceList, OnceListWithLen, OnceListWithTail, OnceListWithTailLen};
+use ::std::cell::Cell;
+use ::std::rc::Rc;
+
+/// Counts the drops of the values.
+struct DropFlag(Rc<Cell<usize>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn test_push_extend_iterate() {
+    let bump = Bump::new();
+    let list = OnceListWithTailLen::new_in_bump(&bump);
+    assert_eq!(list.push_back(1), &1);
+    list.extend(2..=100);
+    assert_eq!(list.len(), 100);
+    assert_eq!(list.back(), Some(&100));
+    assert_eq!(list.iter().sum::<i32>(), 5050);
+    assert!(bump.allocated_bytes() > 0);
+}
+
+#[test]
+fn test_all_cache_modes() {
+    let bump = Bump::new();
+    let list1 = OnceList::new_in_bump(&bump);
+    let list2 = OnceListWithLen::new_in_bump(&bump);
+    let list3 = OnceListWithTail::new_in_bump(&bump);
+    // Interleaving the allocations of several lists in the same arena is fine.
+    for i in 0..10 {
+        list1.push_back(i);
+        list2.push_back(i);
+        list3.push_back(i);
+    }
+    assert!(list1.iter().eq(list2.iter()));
+    assert!(list2.iter().eq(list3.iter()));
+}
+
+#[test]
+fn test_remove() {
+    let bump = Bump::new();
+    let mut list = OnceListWithTail::new_in_bump(&bump);
+    list.extend(["a".to_string(), "b".to_string(), "c".to_string()]);
+    assert_eq!(list.remove(|s| s == "b"), Some("b".to_string()));
+    assert_eq!(list.pop_front(), Some("a".to_string()));
+    list.push_back("d".to_string());
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), vec!["c", "d"]);
+}
+
+#[test]
+fn test_unsized() {
+    let bump = Bump::new();
+    let list = OnceList::<str, _>::new_in_bump(&bump);
+    list.extend_strs(["hello", "world"]);
+    assert_eq!(list.iter().collect::<Vec<_>>(), vec!["hello", "world"]);
+}
+
+#[test]
+fn test_drop_runs_destructors() {
+    let drops = Rc::new(Cell::new(0));
+    let bump = Bump::new();
+    {
+        let mut list = OnceList::new_in_bump(&bump);
+        list.extend((0..3).map(|_| DropFlag(drops.clone())));
+        drop(list.pop_front());
+        assert_eq!(drops.get(), 1);
+    }
+    // The arena does not run destructors, but the list does.
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_forget_nodes() {
+    let drops = Rc::new(Cell::new(0));
+    let mut bump = Bump::new();
+    {
+        let list = OnceListWithTailLen::new_in_bump(&bump);
+        list.extend((0..1000).map(|_| DropFlag(drops.clone())));
+        list.forget_nodes();
+        assert_eq!(drops.get(), 1000);
+    }
+    bump.reset();
+
+    let list = OnceList::<DropFlag, _>::new_in_bump(&bump);
+    list.forget_nodes();
+    assert_eq!(drops.get(), 1000);
+}
+
+#[test]
+fn test_clone_in_global() {
+    let mut bump = Bump::new();
+    let cloned = {
+        let list = OnceListWithTailLen::new_in_bump(&bump);
+        list.extend(["a".to_string(), "b".to_string()]);
+        list.clone_in(::allocator_api2::alloc::Global)
+    };
+    // The clone outlives the arena's contents.
+    bump.reset();
+    cloned.push_back("c".to_string());
+    assert_eq!(cloned.len(), 3);
+    assert_eq!(cloned.into_iter().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+}